@@ -0,0 +1,163 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A global allocator that counts the allocations of each thread, so the
+/// memory used by a part can be measured with [`measure`]. The binary
+/// installs it with `#[global_allocator]`.
+pub struct CountingAllocator;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    current: usize,
+    peak: usize,
+    allocations: u64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters {
+            current: 0,
+            peak: 0,
+            allocations: 0,
+        })
+    };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+fn record(grown: usize, shrunk: usize, allocations: u64) {
+    // The counters are gone while the thread is being torn down.
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        c.current = (c.current + grown).saturating_sub(shrunk);
+        c.peak = c.peak.max(c.current);
+        c.allocations += allocations;
+        counters.set(c);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            INSTALLED.store(true, Ordering::Relaxed);
+            record(layout.size(), 0, 1);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            INSTALLED.store(true, Ordering::Relaxed);
+            record(layout.size(), 0, 1);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size(), 0);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size(), 1);
+        }
+        new_ptr
+    }
+}
+
+/// Heap used while running a piece of code on the current thread.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Usage {
+    /// Most bytes allocated at once, beyond what was allocated before.
+    pub peak: usize,
+    /// Allocations and reallocations made.
+    pub allocations: u64,
+}
+
+/// Runs `f` and measures its heap usage, or returns `None` for the usage
+/// when the counting allocator is not installed.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    let before = COUNTERS.with(|counters| {
+        let before = counters.get();
+        counters.set(Counters {
+            peak: before.current,
+            ..before
+        });
+        before
+    });
+    let result = f();
+    let usage = COUNTERS.with(|counters| {
+        let after = counters.get();
+        counters.set(Counters {
+            peak: after.peak.max(before.peak),
+            ..after
+        });
+        Usage {
+            peak: after.peak - before.current,
+            allocations: after.allocations - before.allocations,
+        }
+    });
+    (result, INSTALLED.load(Ordering::Relaxed).then_some(usage))
+}
+
+/// Formats a byte count with a binary unit suited to its magnitude.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{}B", bytes),
+        _ => format!("{:.2}{}", size, UNITS[unit]),
+    }
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cfg(test)]
+mod test {
+    use std::hint::black_box;
+
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let (len, usage) = measure(|| {
+            let small = black_box(vec![0u8; 1000]);
+            drop(small);
+            let big = black_box(vec![0u64; 1000]);
+            big.len()
+        });
+        assert_eq!(len, 1000);
+        let usage = usage.unwrap();
+        assert_eq!(usage.allocations, 2);
+        assert_eq!(usage.peak, 8000);
+
+        let (_, outer) = measure(|| {
+            let kept = black_box(vec![0u8; 4096]);
+            let (_, inner) = measure(|| black_box(vec![0u8; 100]).len());
+            assert_eq!(inner.unwrap().peak, 100);
+            kept
+        });
+        assert_eq!(outer.unwrap().peak, 4196);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.50KiB");
+        assert_eq!(format_bytes(8 * 1024 * 1024), "8.00MiB");
+    }
+}
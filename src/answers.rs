@@ -0,0 +1,406 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{read_to_string, write},
+    io,
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    input::InputSource,
+    registry::Entry,
+    runner,
+    solution::{Answer, Part, Unsolved},
+};
+
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.txt";
+
+/// How long [`verify`] gives each day. Far longer than any day should take,
+/// since this only guards against a day that hangs.
+pub const VERIFY_TIMEOUT_SECS: u64 = 60;
+
+/// Why the site rejected a submitted answer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Rejection {
+    TooHigh,
+    TooLow,
+    Wrong,
+}
+
+impl Rejection {
+    fn parse(s: &str) -> Result<Self> {
+        match s {
+            "high" => Ok(Rejection::TooHigh),
+            "low" => Ok(Rejection::TooLow),
+            "wrong" => Ok(Rejection::Wrong),
+            other => bail!("invalid rejection {}", other),
+        }
+    }
+
+    /// The short form used in answer files.
+    fn key(&self) -> &'static str {
+        match self {
+            Rejection::TooHigh => "high",
+            Rejection::TooLow => "low",
+            Rejection::Wrong => "wrong",
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::TooHigh => write!(f, "too high"),
+            Rejection::TooLow => write!(f, "too low"),
+            Rejection::Wrong => write!(f, "wrong"),
+        }
+    }
+}
+
+/// Known-good answers, one line per part: `<year> <day> <part> <answer>`.
+/// Answers the site rejected are kept as
+/// `x <year> <day> <part> <high|low|wrong> <answer>`.
+///
+/// Newlines in multi-line answers are stored as `\n` and backslashes as `\\`.
+/// Comment lines starting with `#` at the top of the file are kept when the
+/// store is written back; those further down are dropped.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AnswerStore {
+    header: Vec<String>,
+    answers: BTreeMap<(u16, u8, Part), String>,
+    rejected: BTreeMap<(u16, u8, Part, String), Rejection>,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> Result<String> {
+    let mut result = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('\\') => result.push('\\'),
+                other => bail!("invalid escape sequence: \\{}", other.unwrap_or(' ')),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Ok(result)
+}
+
+impl AnswerStore {
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut store = AnswerStore::default();
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim_end();
+            if line.starts_with('#') && store.answers.is_empty() && store.rejected.is_empty() {
+                store.header.push(line.to_string());
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (rejected, line) = match line.strip_prefix("x ") {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let parse_line = || -> Result<_> {
+                let mut fields = line.splitn(if rejected { 5 } else { 4 }, ' ');
+                let mut field = |name| fields.next().ok_or_else(|| anyhow!("missing {}", name));
+                let year = field("year")?.parse()?;
+                let day = field("day")?.parse()?;
+                let part = match field("part")? {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    other => bail!("invalid part {}", other),
+                };
+                let rejection = match rejected {
+                    true => Some(Rejection::parse(field("rejection")?)?),
+                    false => None,
+                };
+                let answer = unescape(field("answer")?)?;
+                Ok((year, day, part, rejection, answer))
+            };
+            let (year, day, part, rejection, answer) =
+                parse_line().with_context(|| format!("invalid answer on line {}", n + 1))?;
+            match rejection {
+                Some(rejection) => {
+                    store.rejected.insert((year, day, part, answer), rejection);
+                }
+                None => {
+                    store.answers.insert((year, day, part), answer);
+                }
+            }
+        }
+        Ok(store)
+    }
+
+    /// Loads the store from `path`, treating a missing file as empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        match read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("could not read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write(path, self.to_string()).with_context(|| format!("could not write {}", path.display()))
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, part: Part, answer: &Answer) {
+        self.answers.insert((year, day, part), answer.to_string());
+    }
+
+    pub fn reject(&mut self, year: u16, day: u8, part: Part, answer: &Answer, why: Rejection) {
+        self.rejected
+            .insert((year, day, part, answer.to_string()), why);
+    }
+
+    /// Explains why `answer` is known to be wrong, either because it was
+    /// rejected before or because it lies beyond a rejected bound.
+    pub fn known_wrong(&self, year: u16, day: u8, part: Part, answer: &Answer) -> Option<String> {
+        let text = answer.to_string();
+        let rejected = self
+            .rejected
+            .range((year, day, part, String::new())..)
+            .take_while(|((y, d, p, _), _)| (*y, *d, *p) == (year, day, part));
+        for ((_, _, _, previous), &why) in rejected {
+            if *previous == text {
+                return Some(format!("{} was already rejected as {}", text, why));
+            }
+            if let (Answer::Int(n), Ok(bound)) = (answer, previous.parse::<i64>()) {
+                match why {
+                    Rejection::TooHigh if *n >= bound => {
+                        return Some(format!("{} was too high, so {} is too", bound, n))
+                    }
+                    Rejection::TooLow if *n <= bound => {
+                        return Some(format!("{} was too low, so {} is too", bound, n))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Display for AnswerStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.header {
+            writeln!(f, "{}", line)?;
+        }
+        for ((year, day, part), answer) in &self.answers {
+            writeln!(f, "{} {} {} {}", year, day, part, escape(answer))?;
+        }
+        for ((year, day, part, answer), why) in &self.rejected {
+            writeln!(
+                f,
+                "x {} {} {} {} {}",
+                year,
+                day,
+                part,
+                why.key(),
+                escape(answer)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: Answer,
+    },
+    /// The part ran but there is no stored answer to compare against.
+    Missing(Answer),
+    Unsolved,
+    Error(String),
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error(_))
+    }
+}
+
+pub struct Check {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub verdict: Verdict,
+}
+
+fn judge(store: &AnswerStore, entry: &Entry, part: Part, answer: Result<Answer>) -> Verdict {
+    match (answer, store.get(entry.year, entry.day, part)) {
+        (Err(e), _) if e.is::<Unsolved>() => Verdict::Unsolved,
+        (Err(e), _) => Verdict::Error(format!("{:#}", e)),
+        (Ok(answer), None) => Verdict::Missing(answer),
+        (Ok(answer), Some(expected)) if answer.to_string() == expected => Verdict::Pass,
+        (Ok(answer), Some(expected)) => Verdict::Fail {
+            expected: expected.to_string(),
+            actual: answer,
+        },
+    }
+}
+
+/// Runs both parts of `entry` and compares them against `store`. A part that
+/// panics or runs past [`VERIFY_TIMEOUT_SECS`] is an error rather than the
+/// end of the run.
+pub fn verify(entry: &'static Entry, source: &InputSource, store: &AnswerStore) -> Vec<Check> {
+    let check = |part, verdict| Check {
+        year: entry.year,
+        day: entry.day,
+        part,
+        verdict,
+    };
+
+    match source.load(entry.year, entry.day).and_then(|input| {
+        runner::run_guarded(entry, input, Duration::from_secs(VERIFY_TIMEOUT_SECS))
+    }) {
+        Ok(outcomes) => outcomes
+            .into_iter()
+            .map(|outcome| {
+                check(
+                    outcome.part,
+                    judge(store, entry, outcome.part, outcome.answer),
+                )
+            })
+            .collect(),
+        Err(e) => Part::ALL
+            .iter()
+            .map(|&part| check(part, Verdict::Error(format!("{:#}", e))))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::Solution;
+
+    #[test]
+    fn test_parse_store() {
+        let store = AnswerStore::parse(
+            "# year day part answer\n2015 1 1 232\n\n2016 8 2 #..#\\n.##.\n2018 2 2 wmlnjev\n",
+        )
+        .unwrap();
+        assert_eq!(store.get(2015, 1, Part::One), Some("232"));
+        assert_eq!(store.get(2016, 8, Part::Two), Some("#..#\n.##."));
+        assert_eq!(store.get(2018, 2, Part::Two), Some("wmlnjev"));
+        assert_eq!(store.get(2015, 1, Part::Two), None);
+
+        assert!(AnswerStore::parse("2015 1 3 232").is_err());
+        assert!(AnswerStore::parse("2015 1").is_err());
+    }
+
+    #[test]
+    fn test_keeps_header() {
+        let mut store = AnswerStore::parse(
+            "# answers\n# <year> <day> <part> <answer>\n\n2015 1 1 232\n# old\n",
+        )
+        .unwrap();
+        store.insert(2015, 1, Part::Two, &Answer::Int(1783));
+        assert_eq!(
+            store.to_string(),
+            "# answers\n# <year> <day> <part> <answer>\n2015 1 1 232\n2015 1 2 1783\n"
+        );
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let mut store = AnswerStore::default();
+        store.insert(2016, 8, Part::Two, &Answer::Grid("#.\\\n.#".to_string()));
+        store.insert(2015, 10, Part::One, &Answer::Int(360154));
+        assert_eq!(AnswerStore::parse(&store.to_string()).unwrap(), store);
+    }
+
+    #[test]
+    fn test_rejected() {
+        let mut store = AnswerStore::parse("2015 1 1 232\nx 2015 1 2 high 2000\n").unwrap();
+        store.reject(2015, 1, Part::Two, &Answer::Int(10), Rejection::TooLow);
+        store.reject(2016, 5, Part::One, &"abc def".into(), Rejection::Wrong);
+        assert_eq!(
+            store.to_string(),
+            "2015 1 1 232\nx 2015 1 2 low 10\nx 2015 1 2 high 2000\nx 2016 5 1 wrong abc def\n"
+        );
+        assert_eq!(AnswerStore::parse(&store.to_string()).unwrap(), store);
+        assert_eq!(store.get(2015, 1, Part::Two), None);
+
+        let known_wrong =
+            |year, day, part, answer: Answer| store.known_wrong(year, day, part, &answer).is_some();
+        assert!(known_wrong(2015, 1, Part::Two, Answer::Int(2000)));
+        assert!(known_wrong(2015, 1, Part::Two, Answer::Int(2500)));
+        assert!(known_wrong(2015, 1, Part::Two, Answer::Int(3)));
+        assert!(!known_wrong(2015, 1, Part::Two, Answer::Int(1771)));
+        assert!(!known_wrong(2015, 1, Part::One, Answer::Int(2500)));
+        assert!(known_wrong(2016, 5, Part::One, "abc def".into()));
+        assert!(AnswerStore::parse("x 2015 1 2 huge 2000").is_err());
+    }
+
+    #[test]
+    fn test_judge() {
+        let store = AnswerStore::parse("2016 2 1 12578").unwrap();
+        let entry = crate::registry::find(2016, 2).unwrap();
+
+        assert!(matches!(
+            judge(&store, entry, Part::One, Ok(Answer::Int(12578))),
+            Verdict::Pass
+        ));
+        assert!(judge(&store, entry, Part::One, Ok("1234".into())).is_failure());
+        assert!(matches!(
+            judge(&store, entry, Part::Two, Ok("1234".into())),
+            Verdict::Missing(_)
+        ));
+        assert!(matches!(
+            judge(&store, entry, Part::Two, Err(Unsolved.into())),
+            Verdict::Unsolved
+        ));
+    }
+
+    struct Panicking;
+
+    impl Solution for Panicking {
+        type Input = ();
+
+        fn parse(_input: &str) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Result<Answer> {
+            Ok(Answer::Int(1))
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Answer> {
+            panic!("no answer")
+        }
+    }
+
+    static PANICKING: Entry = Entry::new(2000, 1, &Panicking);
+
+    #[test]
+    fn test_verify_panic() {
+        let store = AnswerStore::parse("2000 1 1 1").unwrap();
+        let source = InputSource::Literal(String::new());
+        let checks = verify(&PANICKING, &source, &store);
+        assert!(matches!(checks[0].verdict, Verdict::Pass));
+        assert!(
+            matches!(&checks[1].verdict, Verdict::Error(e) if e == "panicked: no answer"),
+            "{:?}",
+            checks[1].verdict
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{read_to_string, write},
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    registry::Entry,
+    solution::{Part, Unsolved},
+};
+
+pub const DEFAULT_RUNS: usize = 10;
+/// Percentage by which a median may grow before it counts as a regression.
+pub const DEFAULT_THRESHOLD: u32 = 10;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    Parse,
+    Part(Part),
+}
+
+impl Stage {
+    fn parse(s: &str) -> Result<Self> {
+        match s {
+            "parse" => Ok(Stage::Parse),
+            "1" => Ok(Stage::Part(Part::One)),
+            "2" => Ok(Stage::Part(Part::Two)),
+            other => bail!("invalid stage {}", other),
+        }
+    }
+
+    /// The short form used in baseline files.
+    fn key(&self) -> String {
+        match self {
+            Stage::Parse => "parse".to_string(),
+            Stage::Part(part) => part.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => f.pad("parse"),
+            Stage::Part(part) => f.pad(&format!("part {}", part)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Stats {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / samples.len() as u32,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Timing {
+    pub year: u16,
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+/// Times parsing and each solved part of `entry` over `runs` runs.
+///
+/// Parts that are not solved yet are skipped; any other error aborts the
+/// benchmark since its timings would be meaningless.
+pub fn bench(entry: &Entry, input: &str, runs: usize) -> Result<Vec<Timing>> {
+    if runs == 0 {
+        bail!("at least one run is needed");
+    }
+    let timing = |stage, samples| Timing {
+        year: entry.year,
+        day: entry.day,
+        stage,
+        stats: Stats::from_samples(samples),
+    };
+
+    let mut samples = Vec::with_capacity(runs);
+    let mut parsed = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = entry.solution.parse_dyn(black_box(input));
+        samples.push(start.elapsed());
+        parsed = Some(result.with_context(|| {
+            format!("failed to parse input for {} day {}", entry.year, entry.day)
+        })?);
+    }
+    let parsed = parsed.expect("at least one run");
+    let mut timings = vec![timing(Stage::Parse, samples)];
+
+    for part in Part::ALL {
+        let mut samples = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let start = Instant::now();
+            let result = entry.solution.part_dyn(&*parsed, part);
+            samples.push(start.elapsed());
+            match result {
+                Ok(answer) => {
+                    black_box(answer);
+                }
+                Err(e) if e.is::<Unsolved>() => break,
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!("{} day {} part {} failed", entry.year, entry.day, part)
+                    })
+                }
+            }
+        }
+        if samples.len() == runs {
+            timings.push(timing(Stage::Part(part), samples));
+        }
+    }
+
+    Ok(timings)
+}
+
+/// Saved timings, one line per stage:
+/// `<year> <day> <stage> <min ns> <median ns> <mean ns>`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    stats: BTreeMap<(u16, u8, Stage), Stats>,
+}
+
+impl Baseline {
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut baseline = Baseline::default();
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse_line = || -> Result<_> {
+                let mut fields = line.split_whitespace();
+                let mut field = |name| fields.next().ok_or_else(|| anyhow!("missing {}", name));
+                let year = field("year")?.parse()?;
+                let day = field("day")?.parse()?;
+                let stage = Stage::parse(field("stage")?)?;
+                let mut nanos =
+                    |name| -> Result<Duration> { Ok(Duration::from_nanos(field(name)?.parse()?)) };
+                let stats = Stats {
+                    min: nanos("min")?,
+                    median: nanos("median")?,
+                    mean: nanos("mean")?,
+                };
+                Ok((year, day, stage, stats))
+            };
+            let (year, day, stage, stats) =
+                parse_line().with_context(|| format!("invalid timing on line {}", n + 1))?;
+            baseline.stats.insert((year, day, stage), stats);
+        }
+        Ok(baseline)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write(path, self.to_string()).with_context(|| format!("could not write {}", path.display()))
+    }
+
+    pub fn get(&self, year: u16, day: u8, stage: Stage) -> Option<&Stats> {
+        self.stats.get(&(year, day, stage))
+    }
+}
+
+impl<'a> FromIterator<&'a Timing> for Baseline {
+    fn from_iter<I: IntoIterator<Item = &'a Timing>>(timings: I) -> Self {
+        Baseline {
+            stats: timings
+                .into_iter()
+                .map(|t| ((t.year, t.day, t.stage), t.stats))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((year, day, stage), stats) in &self.stats {
+            writeln!(
+                f,
+                "{} {} {} {} {} {}",
+                year,
+                day,
+                stage.key(),
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Relative change of the median from `baseline` to `current`, in percent.
+pub fn change_percent(baseline: &Stats, current: &Stats) -> f64 {
+    let before = baseline.median.as_secs_f64();
+    let after = current.median.as_secs_f64();
+    if before == 0.0 {
+        return 0.0;
+    }
+    (after - before) / before * 100.0
+}
+
+pub fn is_regression(baseline: &Stats, current: &Stats, threshold: u32) -> bool {
+    change_percent(baseline, current) > threshold as f64
+}
+
+/// Formats a duration with a unit suited to its magnitude.
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", d.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(vec![ms(5), ms(1), ms(3)]);
+        assert_eq!(
+            stats,
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3)
+            }
+        );
+        let stats = Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(9)]);
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.mean, ms(4));
+    }
+
+    #[test]
+    fn test_bench() {
+        let entry = registry::find(2016, 10).unwrap();
+        let timings = bench(entry, "value 5 goes to bot 2", 3).unwrap();
+        let stages: Vec<_> = timings.iter().map(|t| t.stage).collect();
+        assert_eq!(stages, vec![Stage::Parse]);
+
+        let entry = registry::find(2018, 1).unwrap();
+        let timings = bench(entry, "+1\n-1", 2).unwrap();
+        assert_eq!(timings.len(), 3);
+        assert!(bench(entry, "+1\n-1", 0).is_err());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let timings = [
+            Timing {
+                year: 2015,
+                day: 4,
+                stage: Stage::Parse,
+                stats: Stats::from_samples(vec![Duration::from_nanos(120)]),
+            },
+            Timing {
+                year: 2015,
+                day: 4,
+                stage: Stage::Part(Part::Two),
+                stats: Stats::from_samples(vec![ms(7), ms(9)]),
+            },
+        ];
+        let baseline: Baseline = timings.iter().collect();
+        let text = baseline.to_string();
+        assert_eq!(
+            text,
+            "2015 4 parse 120 120 120\n2015 4 2 7000000 8000000 8000000\n"
+        );
+        assert_eq!(Baseline::parse(&text).unwrap(), baseline);
+        assert!(Baseline::parse("2015 4 3 1 1 1").is_err());
+    }
+
+    #[test]
+    fn test_regression() {
+        let before = Stats::from_samples(vec![ms(10)]);
+        let after = Stats::from_samples(vec![ms(12)]);
+        assert!((change_percent(&before, &after) - 20.0).abs() < 1e-9);
+        assert!(is_regression(&before, &after, 10));
+        assert!(!is_regression(&before, &after, 25));
+        assert!(!is_regression(&after, &before, 10));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.00s");
+    }
+}
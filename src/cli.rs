@@ -0,0 +1,736 @@
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    answers::DEFAULT_ANSWERS_FILE,
+    bench::{DEFAULT_RUNS, DEFAULT_THRESHOLD},
+    compare::DEFAULT_TIMEOUT_SECS,
+    input::{self, InputSource},
+    parse::ParseError,
+    report::Format,
+    solution::Part,
+};
+
+/// Kept here rather than in the client so the command parses without the
+/// `fetch` feature.
+pub const DEFAULT_CONFIG_FILE: &str = "aoc.conf";
+
+pub const USAGE: &str = "usage: aoc_rust <command> [options]
+
+commands:
+    run <year> <day> [part]    run a single day, optionally only one part
+    list                       list every registered day
+    all [--year <year>]        run every registered day and print a summary table
+    verify [--year <year>]     check every registered day against the stored
+                               answers, exiting non-zero on any mismatch
+    bench [<year> <day>]       time parsing and both parts of one day, or of
+                               every registered day (filtered by --year)
+    explore <year> <day>       parse pasted lines of a day's input and call
+                               its helpers interactively
+    watch <year> <day>         rerun the tests and both parts of a day whenever
+                               its module or input changes, showing how the
+                               answers changed
+    compare <year> <day>       run a day on every input in
+                               <input-dir>/YearXXXX/DayN/*.txt and show the
+                               answers side by side, flagging panics and
+                               timeouts
+    status [--year <year>]     show a calendar of solved parts, tests and
+                               input files for each day
+    new <year> <day>           create and register the module of a new day,
+                               along with its empty input file
+    fetch <year> <day>         download the input of a day unless it is
+                               already in the inputs directory
+    submit <year> <day> <part> run one part and submit its answer, recording
+                               the verdict in the answers file
+
+input options:
+    --input-dir <dir>          read inputs from <dir>/YearXXXX/DayN.txt
+                               (defaults to $AOC_INPUT_DIR, then ./inputs)
+    --input <file>             read the input of a single day from <file>
+    --literal <text>           use <text> as the input of a single day
+    --stdin                    read the input of a single day from stdin
+
+logging options (any command):
+    -v, -vv                    print debug, or debug and trace, messages
+                               from solvers to stderr
+    --log <targets>            only print messages from these comma-separated
+                               modules, such as year2015::day7 (implies -vv
+                               unless -v is given)
+
+output options (run and all):
+    --format <format>          text, json or csv; json and csv print one
+                               record per part with its answer, time in
+                               nanoseconds and status (default: text)
+
+all options:
+    --jobs <n>                 run days on <n> threads, 0 for one per CPU
+                               (default: 1)
+
+verify, submit and status options:
+    --answers <file>           answers file to check against (default: answers.txt)
+
+verify options:
+    --record                   store the answers of parts that have none yet
+
+fetch and submit options:
+    --config <file>            file with the `session = <token>` cookie of a
+                               logged in adventofcode.com session
+                               (default: aoc.conf); request times are kept
+                               next to it, such as in aoc.state, so rate
+                               limits also hold between runs
+
+compare options:
+    --timeout <seconds>        time allowed for each input (default: 10)
+
+bench options:
+    --runs <n>                 timed runs per stage (default: 10)
+    --save <file>              save the timings as a baseline
+    --compare <file>           compare against a saved baseline, exiting
+                               non-zero on any regression
+    --threshold <percent>      slowdown of the median counted as a regression
+                               (default: 10)";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        year: u16,
+        day: u8,
+        part: Option<Part>,
+        input: InputSource,
+        format: Format,
+    },
+    List,
+    All {
+        year: Option<u16>,
+        input: InputSource,
+        jobs: usize,
+        format: Format,
+    },
+    Verify {
+        year: Option<u16>,
+        input: InputSource,
+        answers: PathBuf,
+        record: bool,
+    },
+    Bench {
+        year: Option<u16>,
+        day: Option<u8>,
+        input: InputSource,
+        runs: usize,
+        save: Option<PathBuf>,
+        compare: Option<PathBuf>,
+        threshold: u32,
+    },
+    Explore {
+        year: u16,
+        day: u8,
+        input: InputSource,
+    },
+    Watch {
+        year: u16,
+        day: u8,
+        input_dir: PathBuf,
+    },
+    Compare {
+        year: u16,
+        day: u8,
+        input_dir: PathBuf,
+        timeout: Duration,
+    },
+    Status {
+        year: Option<u16>,
+        input_dir: PathBuf,
+        answers: PathBuf,
+    },
+    New {
+        year: u16,
+        day: u8,
+        input_dir: PathBuf,
+    },
+    Fetch {
+        year: u16,
+        day: u8,
+        input_dir: PathBuf,
+        config: PathBuf,
+    },
+    Submit {
+        year: u16,
+        day: u8,
+        part: Part,
+        input: InputSource,
+        answers: PathBuf,
+        config: PathBuf,
+    },
+}
+
+fn parse_year(s: &str) -> Result<u16> {
+    s.parse().with_context(|| format!("invalid year: {}", s))
+}
+
+fn parse_day(s: &str) -> Result<u8> {
+    let day: u8 = s.parse().with_context(|| format!("invalid day: {}", s))?;
+    match day {
+        1..=25 => Ok(day),
+        _ => Err(anyhow!("day must be between 1 and 25, got {}", day)),
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(anyhow!("part must be 1 or 2, got {}", s)),
+    }
+}
+
+const VALUE_OPTIONS: &[&str] = &[
+    "--year",
+    "--input-dir",
+    "--input",
+    "--literal",
+    "--answers",
+    "--runs",
+    "--save",
+    "--compare",
+    "--threshold",
+    "--jobs",
+    "--format",
+    "--config",
+    "--timeout",
+];
+
+const SWITCH_OPTIONS: &[&str] = &["--stdin", "--record"];
+
+/// Options that may appear anywhere on the command line. Each command takes
+/// the ones it understands; anything left over is reported as unsupported.
+#[derive(Debug, Default)]
+struct Options<'a> {
+    given: BTreeMap<&'a str, Option<&'a str>>,
+}
+
+impl<'a> Options<'a> {
+    fn value(&mut self, name: &str) -> Option<&'a str> {
+        self.given.remove(name).flatten()
+    }
+
+    fn switch(&mut self, name: &str) -> bool {
+        self.given.remove(name).is_some()
+    }
+
+    fn year(&mut self) -> Result<Option<u16>> {
+        self.value("--year").map(parse_year).transpose()
+    }
+
+    fn parsed<T: FromStr>(&mut self, name: &str, default: T) -> Result<T> {
+        match self.value(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| anyhow!("invalid value for {}: {}", name, value)),
+            None => Ok(default),
+        }
+    }
+
+    fn day_input(&mut self) -> Result<InputSource> {
+        let sources = [
+            self.value("--input-dir")
+                .map(|dir| InputSource::Directory(PathBuf::from(dir))),
+            self.value("--input")
+                .map(|file| InputSource::File(PathBuf::from(file))),
+            self.value("--literal")
+                .map(|text| InputSource::Literal(text.to_string())),
+            self.switch("--stdin").then_some(InputSource::Stdin),
+        ];
+        let mut given = sources.into_iter().flatten();
+        match (given.next(), given.next()) {
+            (None, _) => Ok(InputSource::default()),
+            (Some(source), None) => Ok(source),
+            (Some(_), Some(_)) => {
+                bail!("only one of --input-dir, --input, --literal and --stdin may be given")
+            }
+        }
+    }
+
+    fn dir_input(&mut self) -> Result<InputSource> {
+        match self.day_input()? {
+            source @ InputSource::Directory(_) => Ok(source),
+            _ => bail!("only --input-dir can be used when running several days"),
+        }
+    }
+
+    fn input_dir(&mut self) -> PathBuf {
+        self.value("--input-dir")
+            .map_or_else(input::default_dir, PathBuf::from)
+    }
+
+    fn answers(&mut self) -> PathBuf {
+        self.value("--answers")
+            .map_or_else(|| PathBuf::from(DEFAULT_ANSWERS_FILE), PathBuf::from)
+    }
+
+    fn config(&mut self) -> PathBuf {
+        self.value("--config")
+            .map_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE), PathBuf::from)
+    }
+
+    fn finish(self) -> Result<()> {
+        match self.given.keys().next() {
+            Some(name) => bail!("{} is not supported by this command", name),
+            None => Ok(()),
+        }
+    }
+}
+
+fn split_options(args: &[String]) -> Result<(Vec<&str>, Options<'_>)> {
+    let mut positional = Vec::new();
+    let mut options = Options::default();
+    let mut args = args.iter().map(String::as_str);
+
+    while let Some(arg) = args.next() {
+        let value = if VALUE_OPTIONS.contains(&arg) {
+            Some(
+                args.next()
+                    .ok_or_else(|| anyhow!("missing value for {}", arg))?,
+            )
+        } else if SWITCH_OPTIONS.contains(&arg) {
+            None
+        } else if arg.starts_with("--") {
+            bail!("unknown option {}\n\n{}", arg, USAGE);
+        } else {
+            positional.push(arg);
+            continue;
+        };
+        if options.given.insert(arg, value).is_some() {
+            bail!("{} was given more than once", arg);
+        }
+    }
+
+    Ok((positional, options))
+}
+
+/// Removes the logging options from `args`, which apply to every command,
+/// and returns the verbosity and the targets to log.
+pub fn take_log_options(args: &mut Vec<String>) -> Result<(u8, Vec<String>)> {
+    let mut verbosity = None;
+    let mut targets = Vec::new();
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.drain(..);
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-v" => *verbosity.get_or_insert(0) += 1,
+            "-vv" => *verbosity.get_or_insert(0) += 2,
+            "--log" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for --log"))?;
+                targets.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(String::from),
+                );
+            }
+            _ => rest.push(arg),
+        }
+    }
+    drop(iter);
+    *args = rest;
+
+    let verbosity = match (verbosity, targets.is_empty()) {
+        (Some(verbosity), _) => verbosity.min(2),
+        (None, false) => 2,
+        (None, true) => 0,
+    };
+    Ok((verbosity, targets))
+}
+
+/// Formats an error for the terminal, showing the offending input line when
+/// the error comes from a failed parse.
+pub fn render_error(error: &anyhow::Error) -> String {
+    match error.chain().find_map(|e| e.downcast_ref::<ParseError>()) {
+        Some(parse_error) => {
+            let context: Vec<String> = error
+                .chain()
+                .take_while(|e| !e.is::<ParseError>())
+                .map(|e| e.to_string())
+                .collect();
+            if context.is_empty() {
+                format!("error: {}", parse_error.render())
+            } else {
+                format!("error: {}\n{}", context.join(": "), parse_error.render())
+            }
+        }
+        None => format!("error: {:#}", error),
+    }
+}
+
+impl Command {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let (positional, mut options) = split_options(args)?;
+        let command = match positional.as_slice() {
+            ["run", year, day] => Command::Run {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                part: None,
+                input: options.day_input()?,
+                format: options.parsed("--format", Format::Text)?,
+            },
+            ["run", year, day, part] => Command::Run {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                part: Some(parse_part(part)?),
+                input: options.day_input()?,
+                format: options.parsed("--format", Format::Text)?,
+            },
+            ["list"] => Command::List,
+            ["all"] => Command::All {
+                year: options.year()?,
+                input: options.dir_input()?,
+                jobs: options.parsed("--jobs", 1)?,
+                format: options.parsed("--format", Format::Text)?,
+            },
+            ["verify"] => Command::Verify {
+                year: options.year()?,
+                input: options.dir_input()?,
+                answers: options.answers(),
+                record: options.switch("--record"),
+            },
+            ["bench", rest @ ..] => {
+                let (year, day, input) = match rest {
+                    [] => (options.year()?, None, options.dir_input()?),
+                    [year, day] => (
+                        Some(parse_year(year)?),
+                        Some(parse_day(day)?),
+                        options.day_input()?,
+                    ),
+                    _ => bail!("usage: bench [<year> <day>] [options]\n\n{}", USAGE),
+                };
+                Command::Bench {
+                    year,
+                    day,
+                    input,
+                    runs: options.parsed("--runs", DEFAULT_RUNS)?,
+                    save: options.value("--save").map(PathBuf::from),
+                    compare: options.value("--compare").map(PathBuf::from),
+                    threshold: options.parsed("--threshold", DEFAULT_THRESHOLD)?,
+                }
+            }
+            ["explore", year, day] => Command::Explore {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                input: options.day_input()?,
+            },
+            ["watch", year, day] => Command::Watch {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                input_dir: options.input_dir(),
+            },
+            ["compare", year, day] => Command::Compare {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                input_dir: options.input_dir(),
+                timeout: Duration::from_secs(options.parsed("--timeout", DEFAULT_TIMEOUT_SECS)?),
+            },
+            ["status"] => Command::Status {
+                year: options.year()?,
+                input_dir: options.input_dir(),
+                answers: options.answers(),
+            },
+            ["new", year, day] => Command::New {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                input_dir: options.input_dir(),
+            },
+            ["fetch", year, day] => Command::Fetch {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                input_dir: options.input_dir(),
+                config: options.config(),
+            },
+            ["submit", year, day, part] => Command::Submit {
+                year: parse_year(year)?,
+                day: parse_day(day)?,
+                part: parse_part(part)?,
+                input: options.day_input()?,
+                answers: options.answers(),
+                config: options.config(),
+            },
+            _ => bail!("unrecognized arguments: {:?}\n\n{}", positional, USAGE),
+        };
+
+        options.finish()?;
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            Command::parse(&args("run 2015 7")).unwrap(),
+            Command::Run {
+                year: 2015,
+                day: 7,
+                part: None,
+                input: InputSource::default(),
+                format: Format::Text,
+            }
+        );
+        assert_eq!(
+            Command::parse(&args("run 2016 8 2")).unwrap(),
+            Command::Run {
+                year: 2016,
+                day: 8,
+                part: Some(Part::Two),
+                input: InputSource::default(),
+                format: Format::Text,
+            }
+        );
+        assert!(Command::parse(&args("run 2016 26")).is_err());
+        assert!(Command::parse(&args("run 2016 8 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_run_input() {
+        assert_eq!(
+            Command::parse(&args("run 2015 4 --literal abcdef")).unwrap(),
+            Command::Run {
+                year: 2015,
+                day: 4,
+                part: None,
+                input: InputSource::Literal("abcdef".to_string()),
+                format: Format::Text,
+            }
+        );
+        assert_eq!(
+            Command::parse(&args("run --stdin 2015 4 1 --format json")).unwrap(),
+            Command::Run {
+                year: 2015,
+                day: 4,
+                part: Some(Part::One),
+                input: InputSource::Stdin,
+                format: Format::Json,
+            }
+        );
+        assert!(Command::parse(&args("run 2015 4 --stdin --input a.txt")).is_err());
+        assert!(Command::parse(&args("run 2015 4 --literal")).is_err());
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(
+            Command::parse(&args("all")).unwrap(),
+            Command::All {
+                year: None,
+                input: InputSource::default(),
+                jobs: 1,
+                format: Format::Text,
+            }
+        );
+        assert_eq!(
+            Command::parse(&args(
+                "all --year 2017 --input-dir team --jobs 4 --format csv"
+            ))
+            .unwrap(),
+            Command::All {
+                year: Some(2017),
+                input: InputSource::Directory(PathBuf::from("team")),
+                jobs: 4,
+                format: Format::Csv,
+            }
+        );
+        assert!(Command::parse(&args("all --year")).is_err());
+        assert!(Command::parse(&args("all --stdin")).is_err());
+        assert!(Command::parse(&args("all --format yaml")).is_err());
+        assert!(Command::parse(&args("verify --format json")).is_err());
+        assert!(Command::parse(&args("list --year 2017")).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        assert_eq!(
+            Command::parse(&args("verify --year 2016 --record")).unwrap(),
+            Command::Verify {
+                year: Some(2016),
+                input: InputSource::default(),
+                answers: PathBuf::from("answers.txt"),
+                record: true,
+            }
+        );
+        assert!(Command::parse(&args("run 2016 1 --record")).is_err());
+        assert!(Command::parse(&args("verify --record --record")).is_err());
+    }
+
+    #[test]
+    fn test_take_log_options() {
+        let mut arguments = args("run -v 2015 7 --log year2015::day7,runner");
+        assert_eq!(
+            take_log_options(&mut arguments).unwrap(),
+            (1, vec!["year2015::day7".to_string(), "runner".to_string()])
+        );
+        assert_eq!(arguments, args("run 2015 7"));
+
+        let mut arguments = args("-vv all -v");
+        assert_eq!(take_log_options(&mut arguments).unwrap(), (2, vec![]));
+        assert_eq!(arguments, args("all"));
+
+        let mut arguments = args("run 2018 5 --log year2018::day5");
+        assert_eq!(take_log_options(&mut arguments).unwrap().0, 2);
+        assert!(take_log_options(&mut args("run 2018 5 --log")).is_err());
+    }
+
+    #[test]
+    fn test_render_error() {
+        let line = "R5, X2";
+        let error = anyhow::Error::from(ParseError::new(line, &line[4..], "L or R").at_line(1))
+            .context("failed to parse input for 2016 day 1");
+        assert_eq!(
+            render_error(&error),
+            "error: failed to parse input for 2016 day 1\n\
+             line 1, column 5: expected L or R, found \"X2\"\n  |\n1 | R5, X2\n  |     ^^"
+        );
+        assert_eq!(
+            render_error(&anyhow!("no input").context("loading 2015 day 1")),
+            "error: loading 2015 day 1: no input"
+        );
+    }
+
+    #[test]
+    fn test_parse_explore() {
+        assert_eq!(
+            Command::parse(&args("explore 2015 7 --input circuit.txt")).unwrap(),
+            Command::Explore {
+                year: 2015,
+                day: 7,
+                input: InputSource::File(PathBuf::from("circuit.txt")),
+            }
+        );
+        assert!(Command::parse(&args("explore 2015")).is_err());
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(
+            Command::parse(&args("watch 2016 8")).unwrap(),
+            Command::Watch {
+                year: 2016,
+                day: 8,
+                input_dir: input::default_dir(),
+            }
+        );
+        assert!(Command::parse(&args("watch 2016 8 --literal x")).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        assert_eq!(
+            Command::parse(&args("compare 2015 7 --input-dir team --timeout 3")).unwrap(),
+            Command::Compare {
+                year: 2015,
+                day: 7,
+                input_dir: PathBuf::from("team"),
+                timeout: Duration::from_secs(3),
+            }
+        );
+        assert!(Command::parse(&args("compare 2015 7 --timeout soon")).is_err());
+        assert!(Command::parse(&args("run 2015 7 --timeout 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(
+            Command::parse(&args("status --year 2016 --input-dir team")).unwrap(),
+            Command::Status {
+                year: Some(2016),
+                input_dir: PathBuf::from("team"),
+                answers: PathBuf::from("answers.txt"),
+            }
+        );
+        assert!(Command::parse(&args("status 2016")).is_err());
+    }
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(
+            Command::parse(&args("new 2017 6 --input-dir team")).unwrap(),
+            Command::New {
+                year: 2017,
+                day: 6,
+                input_dir: PathBuf::from("team"),
+            }
+        );
+        assert!(Command::parse(&args("new 2017 26")).is_err());
+        assert!(Command::parse(&args("new 2017 6 --input a.txt")).is_err());
+    }
+
+    #[test]
+    fn test_parse_fetch() {
+        assert_eq!(
+            Command::parse(&args("fetch 2016 5 --config secret.conf")).unwrap(),
+            Command::Fetch {
+                year: 2016,
+                day: 5,
+                input_dir: input::default_dir(),
+                config: PathBuf::from("secret.conf"),
+            }
+        );
+        assert!(Command::parse(&args("fetch 2016")).is_err());
+        assert!(Command::parse(&args("run 2016 5 --config secret.conf")).is_err());
+    }
+
+    #[test]
+    fn test_parse_submit() {
+        assert_eq!(
+            Command::parse(&args("submit 2015 1 2 --answers mine.txt")).unwrap(),
+            Command::Submit {
+                year: 2015,
+                day: 1,
+                part: Part::Two,
+                input: InputSource::default(),
+                answers: PathBuf::from("mine.txt"),
+                config: PathBuf::from("aoc.conf"),
+            }
+        );
+        assert!(Command::parse(&args("submit 2015 1")).is_err());
+        assert!(Command::parse(&args("submit 2015 1 3")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            Command::parse(&args("bench 2015 4 --runs 3 --compare base.txt")).unwrap(),
+            Command::Bench {
+                year: Some(2015),
+                day: Some(4),
+                input: InputSource::default(),
+                runs: 3,
+                save: None,
+                compare: Some(PathBuf::from("base.txt")),
+                threshold: 10,
+            }
+        );
+        assert_eq!(
+            Command::parse(&args("bench --year 2016 --save base.txt")).unwrap(),
+            Command::Bench {
+                year: Some(2016),
+                day: None,
+                input: InputSource::default(),
+                runs: 10,
+                save: Some(PathBuf::from("base.txt")),
+                compare: None,
+                threshold: 10,
+            }
+        );
+        assert!(Command::parse(&args("bench 2015")).is_err());
+        assert!(Command::parse(&args("bench --runs many")).is_err());
+    }
+}
@@ -0,0 +1,734 @@
+use std::{
+    error, fmt,
+    fs::{self, read_to_string},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    answers::{AnswerStore, Rejection},
+    input,
+    solution::{Answer, Part},
+};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+/// Minimum time between two requests, also across runs sharing a state file.
+pub const DEFAULT_DELAY: Duration = Duration::from_secs(3);
+const USER_AGENT: &str = "github.com/tomasz-lutoborski/AdventOfCode-Rust";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Settings read from a `key = value` file, where `session` is the value of
+/// the `session` cookie of a logged in adventofcode.com browser session.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Config {
+    pub session: String,
+}
+
+impl Config {
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut session = None;
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .with_context(|| format!("expected `key = value` on line {}", n + 1))?;
+            match key.trim() {
+                "session" => session = Some(value.trim().to_string()),
+                other => bail!("unknown setting {} on line {}", other, n + 1),
+            }
+        }
+        match session {
+            Some(session) if !session.is_empty() => Ok(Config { session }),
+            _ => bail!("no session token set, add `session = <token>`"),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string(path).with_context(|| {
+            format!(
+                "could not read {}, it should contain `session = <token>`",
+                path.display()
+            )
+        })?;
+        Self::parse(&content).with_context(|| format!("invalid config {}", path.display()))
+    }
+}
+
+/// Returned when the site does not accept the session token.
+#[derive(Debug)]
+pub struct ExpiredSession;
+
+impl fmt::Display for ExpiredSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the session token was rejected, it has probably expired; \
+             log in again and copy the new session cookie into the config"
+        )
+    }
+}
+
+impl error::Error for ExpiredSession {}
+
+/// The state file kept next to `config`, such as `aoc.state` for `aoc.conf`.
+pub fn state_file(config: &Path) -> PathBuf {
+    config.with_extension("state")
+}
+
+fn to_millis(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn from_millis(millis: &str) -> Option<SystemTime> {
+    let millis = millis.parse().ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_millis(millis))
+}
+
+/// When requests were last made and when answers may be submitted again,
+/// saved between runs so the rate limits hold across separate invocations.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+struct RateState {
+    last_request: Option<SystemTime>,
+    submit_after: Option<SystemTime>,
+}
+
+impl RateState {
+    fn parse(s: &str) -> Result<Self> {
+        let mut state = RateState::default();
+        for (n, line) in s.lines().enumerate() {
+            let (key, value) = line
+                .split_once('=')
+                .with_context(|| format!("expected `key = value` on line {}", n + 1))?;
+            let time = from_millis(value.trim())
+                .with_context(|| format!("expected milliseconds on line {}", n + 1))?;
+            match key.trim() {
+                "last_request" => state.last_request = Some(time),
+                "submit_after" => state.submit_after = Some(time),
+                other => bail!("unknown setting {} on line {}", other, n + 1),
+            }
+        }
+        Ok(state)
+    }
+
+    /// Loads the state in `path`, which is empty if the file does not exist.
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(RateState::default());
+        }
+        let content =
+            read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("invalid state file {}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let mut content = String::new();
+        if let Some(time) = self.last_request {
+            content += &format!("last_request = {}\n", to_millis(time));
+        }
+        if let Some(time) = self.submit_after {
+            content += &format!("submit_after = {}\n", to_millis(time));
+        }
+        fs::write(path, content).with_context(|| format!("could not write {}", path.display()))
+    }
+}
+
+/// A client for the Advent of Code website that waits at least `delay`
+/// between its requests. With a state file, the wait also covers requests
+/// of earlier runs, and submissions respect the wait the site last asked for.
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    delay: Duration,
+    state: RateState,
+    state_file: Option<PathBuf>,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Self {
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(TIMEOUT)
+                .build(),
+            base_url: BASE_URL.to_string(),
+            session: config.session.clone(),
+            delay: DEFAULT_DELAY,
+            state: RateState::default(),
+            state_file: None,
+        }
+    }
+
+    /// Shares request times with other runs through the file at `path`.
+    pub fn with_state_file(mut self, path: &Path) -> Result<Self> {
+        self.state = RateState::load(path)?;
+        self.state_file = Some(path.to_path_buf());
+        Ok(self)
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn save_state(&self) -> Result<()> {
+        match &self.state_file {
+            Some(path) => self.state.save(path),
+            None => Ok(()),
+        }
+    }
+
+    fn wait(&mut self) -> Result<()> {
+        if let Some(last) = self.state.last_request {
+            // A request time in the future means the clock went back, so
+            // wait the whole delay to be safe.
+            let elapsed = last.elapsed().unwrap_or_default();
+            if let Some(remaining) = self.delay.checked_sub(elapsed) {
+                thread::sleep(remaining);
+            }
+        }
+        self.state.last_request = Some(SystemTime::now());
+        self.save_state()
+    }
+
+    /// Fetches `path` and returns the body of a successful response.
+    pub fn get(&mut self, path: &str) -> Result<String> {
+        self.send(path, None)
+    }
+
+    /// Posts `form` to `path` and returns the body of a successful response.
+    pub fn post(&mut self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        self.send(path, Some(form))
+    }
+
+    fn send(&mut self, path: &str, form: Option<&[(&str, &str)]>) -> Result<String> {
+        self.wait()?;
+        let url = format!("{}{}", self.base_url, path);
+        let cookie = format!("session={}", self.session);
+        let response = match form {
+            Some(form) => self.agent.post(&url).set("Cookie", &cookie).send_form(form),
+            None => self.agent.get(&url).set("Cookie", &cookie).call(),
+        };
+        match response {
+            Ok(response) => response
+                .into_string()
+                .with_context(|| format!("could not read the response from {}", url)),
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(status_error(code, &body, &url))
+            }
+            Err(e) => Err(anyhow!(e).context(format!("could not reach {}", url))),
+        }
+    }
+
+    pub fn input(&mut self, year: u16, day: u8) -> Result<String> {
+        self.get(&format!("/{}/day/{}/input", year, day))
+            .with_context(|| format!("could not download the input for {} day {}", year, day))
+    }
+
+    pub fn submit(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: Part,
+        answer: &Answer,
+    ) -> Result<Submission> {
+        if let Some(left) = self
+            .state
+            .submit_after
+            .and_then(|after| after.duration_since(SystemTime::now()).ok())
+        {
+            bail!(
+                "the site asked to wait before answering again, {}s left",
+                left.as_secs() + 1
+            );
+        }
+        let level = part.to_string();
+        let answer = answer.to_string();
+        let page = self
+            .post(
+                &format!("/{}/day/{}/answer", year, day),
+                &[("level", &level), ("answer", &answer)],
+            )
+            .with_context(|| format!("could not submit {} day {} part {}", year, day, part))?;
+        let submission = Submission::classify(&page)?;
+        if let Submission::RateLimited(Some(wait)) = submission {
+            self.state.submit_after = SystemTime::now().checked_add(wait);
+            self.save_state()?;
+        }
+        Ok(submission)
+    }
+}
+
+/// How the site responded to a submitted answer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Submission {
+    Correct,
+    Rejected(Rejection),
+    /// An answer was submitted too recently, with the time left to wait if
+    /// the site said how long.
+    RateLimited(Option<Duration>),
+}
+
+impl Submission {
+    /// Classifies the page returned for a submission by the message in its
+    /// `<article>`.
+    pub fn classify(page: &str) -> Result<Self> {
+        let message = article_text(page);
+        if message.contains("That's the right answer") {
+            Ok(Submission::Correct)
+        } else if message.contains("answer is too high") {
+            Ok(Submission::Rejected(Rejection::TooHigh))
+        } else if message.contains("answer is too low") {
+            Ok(Submission::Rejected(Rejection::TooLow))
+        } else if message.contains("That's not the right answer") {
+            Ok(Submission::Rejected(Rejection::Wrong))
+        } else if message.contains("You gave an answer too recently") {
+            Ok(Submission::RateLimited(wait_time(&message)))
+        } else if message.contains("Did you already complete it?") {
+            bail!("this part is already solved or not unlocked yet")
+        } else {
+            bail!("unexpected response to the submission: {}", message)
+        }
+    }
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Submission::Correct => write!(f, "correct"),
+            Submission::Rejected(why) => write!(f, "{}", why),
+            Submission::RateLimited(Some(wait)) => {
+                write!(f, "rate limited, wait {}s", wait.as_secs())
+            }
+            Submission::RateLimited(None) => write!(f, "rate limited"),
+        }
+    }
+}
+
+/// The text of the first `<article>` of `page` without its markup, or the
+/// whole page if there is none.
+fn article_text(page: &str) -> String {
+    let article = page
+        .find("<article")
+        .and_then(|start| {
+            let end = page[start..].find("</article>")?;
+            Some(&page[start..start + end])
+        })
+        .unwrap_or(page);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses the wait from messages like "you have 1m 5s left to wait".
+fn wait_time(message: &str) -> Option<Duration> {
+    let before = message.split(" left to wait").next()?;
+    let words: Vec<&str> = before.split_whitespace().collect();
+    let mut seconds = 0;
+    let mut found = false;
+    for word in words.iter().rev() {
+        let (number, unit) = match word.strip_suffix('s') {
+            Some(number) => (number, 1),
+            None => match word.strip_suffix('m') {
+                Some(number) => (number, 60),
+                None => break,
+            },
+        };
+        match number.parse::<u64>() {
+            Ok(n) => seconds += n * unit,
+            Err(_) => break,
+        }
+        found = true;
+    }
+    found.then(|| Duration::from_secs(seconds))
+}
+
+/// Submits `answer` unless `store` already knows it to be wrong, and records
+/// the verdict of the site in `store`.
+pub fn submit(
+    client: &mut Client,
+    store: &mut AnswerStore,
+    year: u16,
+    day: u8,
+    part: Part,
+    answer: &Answer,
+) -> Result<Submission> {
+    if let Some(known) = store.get(year, day, part) {
+        bail!(
+            "{} day {} part {} is already solved with {}",
+            year,
+            day,
+            part,
+            known
+        );
+    }
+    if let Answer::Grid(_) = answer {
+        bail!("grid answers have to be read and submitted by hand");
+    }
+    if let Some(reason) = store.known_wrong(year, day, part, answer) {
+        bail!("refusing to submit a known wrong answer, {}", reason);
+    }
+    let submission = client.submit(year, day, part, answer)?;
+    match submission {
+        Submission::Correct => store.insert(year, day, part, answer),
+        Submission::Rejected(why) => store.reject(year, day, part, answer, why),
+        Submission::RateLimited(_) => {}
+    }
+    Ok(submission)
+}
+
+fn status_error(code: u16, body: &str, url: &str) -> anyhow::Error {
+    match code {
+        400 | 401 | 403 => ExpiredSession.into(),
+        500 if body.contains("log in") => ExpiredSession.into(),
+        404 => anyhow!("{} was not found, the puzzle may not be unlocked yet", url),
+        429 => anyhow!("the site is rate limiting requests, try again later"),
+        code => anyhow!("unexpected response {} from {}: {}", code, url, body.trim()),
+    }
+}
+
+/// Inputs that exist but are empty, such as those created by `new`, are not
+/// considered downloaded.
+pub fn is_cached(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+/// Downloads the input of a day into `input_dir` unless it is already there,
+/// returning its path and whether it was downloaded.
+pub fn fetch_input(
+    client: &mut Client,
+    input_dir: &Path,
+    year: u16,
+    day: u8,
+) -> Result<(PathBuf, bool)> {
+    let path = input::day_path(input_dir, year, day);
+    if is_cached(&path) {
+        return Ok((path, false));
+    }
+    let input = client.input(year, day)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("could not create {}", parent.display()))?;
+    }
+    fs::write(&path, input).with_context(|| format!("could not write {}", path.display()))?;
+    Ok((path, true))
+}
+
+/// A local HTTP server for tests that answers each connection with the next
+/// of `responses` and returns the requests it received once they run out.
+#[cfg(test)]
+pub(crate) fn stub_server(
+    responses: Vec<(u16, &'static str)>,
+) -> (String, thread::JoinHandle<Vec<String>>) {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (code, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            requests.push(request);
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                code,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, time::Instant};
+
+    use super::*;
+
+    fn client(url: &str) -> Client {
+        let config = Config {
+            session: "abc123".to_string(),
+        };
+        Client::new(&config)
+            .with_base_url(url)
+            .with_delay(Duration::ZERO)
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config::parse("# from the browser\nsession = 53616c7465\n").unwrap();
+        assert_eq!(config.session, "53616c7465");
+        assert!(Config::parse("").is_err());
+        assert!(Config::parse("session =").is_err());
+        assert!(Config::parse("session = abc\ntoken = def").is_err());
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let dir = env::temp_dir().join(format!("aoc_rust_fetch_{}", std::process::id()));
+        let (url, server) = stub_server(vec![(200, "1122\n")]);
+        let mut client = client(&url);
+
+        let (path, downloaded) = fetch_input(&mut client, &dir, 2017, 1).unwrap();
+        assert!(downloaded);
+        assert_eq!(path, dir.join("Year2017/Day1.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1122\n");
+
+        // The stub only answers once, so this must come from the cache.
+        let (_, downloaded) = fetch_input(&mut client, &dir, 2017, 1).unwrap();
+        assert!(!downloaded);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2017/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=abc123"));
+        assert!(requests[0].contains(USER_AGENT));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let (url, server) = stub_server(vec![
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+            ),
+            (404, "Please don't repeatedly request this endpoint."),
+        ]);
+        let mut client = client(&url);
+
+        let error = client.input(2016, 1).unwrap_err();
+        assert!(error.is::<ExpiredSession>());
+        let error = client.input(2030, 1).unwrap_err();
+        assert!(format!("{:#}", error).contains("not be unlocked yet"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_classify() {
+        let page = |message| {
+            format!(
+                "<html><main>\n<article><p>{}</p></article>\n</main></html>",
+                message
+            )
+        };
+        let classify = |message| Submission::classify(&page(message)).unwrap();
+
+        assert_eq!(
+            classify("That's the right answer!  You are <em>one gold star</em> closer."),
+            Submission::Correct
+        );
+        assert_eq!(
+            classify("That's not the right answer; your answer is too high.  If you're stuck..."),
+            Submission::Rejected(Rejection::TooHigh)
+        );
+        assert_eq!(
+            classify("That's not the right answer; your answer is too low."),
+            Submission::Rejected(Rejection::TooLow)
+        );
+        assert_eq!(
+            classify("That's not the right answer.  If you're stuck, make sure..."),
+            Submission::Rejected(Rejection::Wrong)
+        );
+        assert_eq!(
+            classify("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait."),
+            Submission::RateLimited(Some(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            classify("You gave an answer too recently."),
+            Submission::RateLimited(None)
+        );
+        assert!(Submission::classify(&page(
+            "You don't seem to be solving the right level.  Did you already complete it?"
+        ))
+        .is_err());
+        assert!(Submission::classify("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, server) = stub_server(vec![
+            (
+                200,
+                "<article><p>That's not the right answer; your answer is too high.</p></article>",
+            ),
+            (200, "<article><p>That's the right answer!</p></article>"),
+        ]);
+        let mut client = client(&url);
+        let mut store = AnswerStore::default();
+
+        let submission = submit(
+            &mut client,
+            &mut store,
+            2015,
+            1,
+            Part::One,
+            &Answer::Int(300),
+        );
+        assert_eq!(
+            submission.unwrap(),
+            Submission::Rejected(Rejection::TooHigh)
+        );
+        // Known to be too high, so neither reaches the stub.
+        assert!(submit(
+            &mut client,
+            &mut store,
+            2015,
+            1,
+            Part::One,
+            &Answer::Int(300)
+        )
+        .is_err());
+        assert!(submit(
+            &mut client,
+            &mut store,
+            2015,
+            1,
+            Part::One,
+            &Answer::Int(301)
+        )
+        .is_err());
+
+        let submission = submit(
+            &mut client,
+            &mut store,
+            2015,
+            1,
+            Part::One,
+            &Answer::Int(232),
+        );
+        assert_eq!(submission.unwrap(), Submission::Correct);
+        assert_eq!(store.get(2015, 1, Part::One), Some("232"));
+        assert!(submit(
+            &mut client,
+            &mut store,
+            2015,
+            1,
+            Part::One,
+            &Answer::Int(232)
+        )
+        .is_err());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2015/day/1/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=1&answer=300"));
+        assert!(requests[1].ends_with("level=1&answer=232"));
+    }
+
+    #[test]
+    fn test_delay() {
+        let (url, server) = stub_server(vec![(200, "a"), (200, "b")]);
+        let mut client = client(&url).with_delay(Duration::from_millis(200));
+
+        let start = Instant::now();
+        client.get("/a").unwrap();
+        client.get("/b").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_state_across_clients() {
+        let dir = env::temp_dir().join(format!("aoc_rust_state_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let state = state_file(&dir.join("aoc.conf"));
+        assert_eq!(state, dir.join("aoc.state"));
+        let (url, server) = stub_server(vec![
+            (200, "a"),
+            (
+                200,
+                "<article><p>You gave an answer too recently. You have 30s left to wait.</p></article>",
+            ),
+        ]);
+        let run = || {
+            client(&url)
+                .with_delay(Duration::from_millis(200))
+                .with_state_file(&state)
+                .unwrap()
+        };
+
+        // Each run is a new client, as with separate invocations.
+        let start = Instant::now();
+        run().get("/a").unwrap();
+        let submission = run().submit(2015, 1, Part::One, &Answer::Int(1)).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(
+            submission,
+            Submission::RateLimited(Some(Duration::from_secs(30)))
+        );
+
+        // The stub has no responses left, so this must be refused up front.
+        let error = run()
+            .submit(2015, 1, Part::One, &Answer::Int(2))
+            .unwrap_err();
+        assert!(error.to_string().contains("asked to wait"));
+        assert_eq!(server.join().unwrap().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rate_state() {
+        let state = RateState {
+            last_request: from_millis("1700000000123"),
+            submit_after: None,
+        };
+        let path = env::temp_dir().join(format!("aoc_rust_rate_{}.state", std::process::id()));
+        state.save(&path).unwrap();
+        assert_eq!(RateState::load(&path).unwrap(), state);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(RateState::load(&path).unwrap(), RateState::default());
+        assert!(RateState::parse("last_request = soon").is_err());
+        assert!(RateState::parse("next = 1").is_err());
+    }
+}
@@ -0,0 +1,408 @@
+//! Small parser combinators for line-oriented puzzle formats.
+//!
+//! Every parser works on slices of the line being parsed, so a failure knows
+//! exactly where it happened and [`parse_line`] can turn it into a
+//! [`ParseError`] pointing at the offending text. When alternatives fail, the
+//! failure that got furthest into the line is reported, even if an earlier
+//! alternative matched something shorter before the rest of the line failed.
+
+use std::{cmp::Ordering, str::FromStr};
+
+use crate::parse::ParseError;
+
+/// A parser failure: the text found and a description of what was expected
+/// instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    found: &'a str,
+    expected: String,
+}
+
+impl<'a> Failure<'a> {
+    /// A failure at the start of `input`, pointing at its first token: a run
+    /// of letters or digits, a single other character, or nothing at the end.
+    pub fn at(input: &'a str, expected: impl Into<String>) -> Self {
+        let len = match input.chars().next() {
+            None => 0,
+            Some(c) if c.is_ascii_alphabetic() || c.is_ascii_digit() => input
+                .find(|d: char| {
+                    d.is_ascii_alphabetic() != c.is_ascii_alphabetic() || !d.is_ascii_alphanumeric()
+                })
+                .unwrap_or(input.len()),
+            Some(c) => c.len_utf8(),
+        };
+        Failure {
+            found: &input[..len],
+            expected: expected.into(),
+        }
+    }
+
+    /// A failure pointing at all of `found`, such as a token that was read
+    /// but turned out to be invalid.
+    pub fn spanning(found: &'a str, expected: impl Into<String>) -> Self {
+        Failure {
+            found,
+            expected: expected.into(),
+        }
+    }
+
+    fn position(&self) -> usize {
+        self.found.as_ptr() as usize
+    }
+
+    /// The failure that got further into the line, or both expectations if
+    /// they failed at the same place.
+    fn furthest(self, other: Self) -> Self {
+        match self.position().cmp(&other.position()) {
+            Ordering::Less => other,
+            Ordering::Greater => self,
+            Ordering::Equal if self.expected == other.expected => self,
+            Ordering::Equal => Failure {
+                expected: format!("{} or {}", self.expected, other.expected),
+                ..self
+            },
+        }
+    }
+
+    fn into_error(self, line: &str) -> ParseError {
+        ParseError::new(line, self.found, self.expected)
+    }
+}
+
+fn furthest<'a>(a: Option<Failure<'a>>, b: Failure<'a>) -> Failure<'a> {
+    match a {
+        Some(a) => a.furthest(b),
+        None => b,
+    }
+}
+
+fn furthest_hint<'a>(a: Option<Failure<'a>>, b: Option<Failure<'a>>) -> Option<Failure<'a>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.furthest(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// A successful parse: the value, the input left over, and the furthest
+/// failure of any alternative that was given up on along the way.
+#[derive(Debug)]
+pub struct Parsed<'a, T> {
+    pub value: T,
+    pub rest: &'a str,
+    hint: Option<Failure<'a>>,
+}
+
+impl<'a, T> Parsed<'a, T> {
+    pub fn new(value: T, rest: &'a str) -> Self {
+        Parsed {
+            value,
+            rest,
+            hint: None,
+        }
+    }
+}
+
+pub type PResult<'a, T> = Result<Parsed<'a, T>, Failure<'a>>;
+
+/// Something that parses a `T` from the start of its input. Implemented for
+/// any `Fn(&str) -> PResult<T>`, so primitives are plain closures.
+pub trait Parser<'a, T> {
+    fn parse(&self, input: &'a str) -> PResult<'a, T>;
+
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            let parsed = self.parse(input)?;
+            Ok(Parsed {
+                value: f(parsed.value),
+                rest: parsed.rest,
+                hint: parsed.hint,
+            })
+        }
+    }
+
+    /// Like [`map`](Parser::map), failing with `expected` at the parsed text
+    /// when `f` returns `None`.
+    fn try_map<U>(self, expected: &'static str, f: impl Fn(T) -> Option<U>) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            let parsed = self.parse(input)?;
+            let found = &input[..input.len() - parsed.rest.len()];
+            match f(parsed.value) {
+                Some(value) => Ok(Parsed {
+                    value,
+                    rest: parsed.rest,
+                    hint: parsed.hint,
+                }),
+                None => Err(furthest(parsed.hint, Failure::spanning(found, expected))),
+            }
+        }
+    }
+
+    /// Parses this and then `next`, keeping both values.
+    fn then<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, (T, U)>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            let first = self.parse(input)?;
+            let second = next
+                .parse(first.rest)
+                .map_err(|failure| furthest(first.hint.clone(), failure))?;
+            Ok(Parsed {
+                value: (first.value, second.value),
+                rest: second.rest,
+                hint: furthest_hint(first.hint, second.hint),
+            })
+        }
+    }
+
+    /// Parses this and then `next`, keeping only the value of `next`.
+    fn ignore_then<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+    {
+        self.then(next).map(|(_, value)| value)
+    }
+
+    /// Parses this and then `next`, keeping only the value of this.
+    fn then_ignore<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        self.then(next).map(|(value, _)| value)
+    }
+
+    /// Tries this, and `other` on the same input if this fails.
+    fn or(self, other: impl Parser<'a, T>) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| match self.parse(input) {
+            Ok(parsed) => Ok(parsed),
+            Err(failure) => match other.parse(input) {
+                Ok(parsed) => Ok(Parsed {
+                    hint: furthest_hint(Some(failure), parsed.hint),
+                    ..parsed
+                }),
+                Err(other) => Err(failure.furthest(other)),
+            },
+        }
+    }
+
+    /// One or more of this, separated by `separator`.
+    fn separated_by<S>(self, separator: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            let first = self.parse(input)?;
+            let mut values = vec![first.value];
+            let (mut rest, mut hint) = (first.rest, first.hint);
+            loop {
+                let next = separator.parse(rest).and_then(|separated| {
+                    self.parse(separated.rest)
+                        .map_err(|failure| furthest(separated.hint, failure))
+                });
+                match next {
+                    Ok(parsed) => {
+                        values.push(parsed.value);
+                        rest = parsed.rest;
+                        hint = furthest_hint(hint, parsed.hint);
+                    }
+                    Err(failure) => {
+                        return Ok(Parsed {
+                            value: values,
+                            rest,
+                            hint: Some(furthest(hint, failure)),
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces what a failure of this says was expected with `expected`.
+    fn labelled(self, expected: &'static str) -> impl Parser<'a, T>
+    where
+        Self: Sized,
+    {
+        move |input: &'a str| {
+            self.parse(input).map_err(|failure| Failure {
+                expected: expected.to_string(),
+                ..failure
+            })
+        }
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&'a str) -> PResult<'a, T>,
+{
+    fn parse(&self, input: &'a str) -> PResult<'a, T> {
+        self(input)
+    }
+}
+
+/// Exactly `text`.
+pub fn literal<'a>(text: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(text) {
+        Some(rest) => Ok(Parsed::new(&input[..text.len()], rest)),
+        None => Err(Failure::at(input, format!("`{}`", text))),
+    }
+}
+
+/// One or more characters matching `predicate`.
+pub fn take_while1<'a>(
+    expected: &'static str,
+    predicate: impl Fn(char) -> bool,
+) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        let len = input.find(|c| !predicate(c)).unwrap_or(input.len());
+        if len == 0 {
+            return Err(Failure::at(input, expected));
+        }
+        Ok(Parsed::new(&input[..len], &input[len..]))
+    }
+}
+
+/// A letter or underscore followed by any letters, digits or underscores.
+pub fn identifier<'a>() -> impl Parser<'a, &'a str> {
+    move |input: &'a str| {
+        if !input.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return Err(Failure::at(input, "an identifier"));
+        }
+        take_while1("an identifier", |c| c.is_ascii_alphanumeric() || c == '_').parse(input)
+    }
+}
+
+/// An optionally negative decimal integer that fits in `T`.
+pub fn integer<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str| {
+        let digits = input.strip_prefix('-').unwrap_or(input);
+        let len = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        if len == 0 {
+            return Err(Failure::at(digits, "a number"));
+        }
+        let (number, rest) = input.split_at(input.len() - digits.len() + len);
+        match number.parse() {
+            Ok(value) => Ok(Parsed::new(value, rest)),
+            Err(_) => Err(Failure::spanning(number, "a number")),
+        }
+    }
+}
+
+/// Parses all of `line` with `parser`.
+pub fn parse_line<'a, T>(parser: impl Parser<'a, T>, line: &'a str) -> Result<T, ParseError> {
+    let parsed = parser
+        .parse(line)
+        .map_err(|failure| failure.into_error(line))?;
+    if !parsed.rest.is_empty() {
+        let failure = furthest(parsed.hint, Failure::at(parsed.rest, "end of line"));
+        return Err(failure.into_error(line));
+    }
+    Ok(parsed.value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Op {
+        Add(u32),
+        Name(String),
+    }
+
+    fn pair<'a>() -> impl Parser<'a, (u32, u32)> {
+        integer().then_ignore(literal(",")).then(integer())
+    }
+
+    fn list<'a>() -> impl Parser<'a, Vec<u32>> {
+        integer().separated_by(literal(", "))
+    }
+
+    fn op<'a>() -> impl Parser<'a, Op> {
+        literal("add ")
+            .ignore_then(integer())
+            .map(Op::Add)
+            .or(identifier().map(|s| Op::Name(s.to_string())))
+    }
+
+    fn digit<'a>() -> impl Parser<'a, u32> {
+        integer().try_map("a digit", |n: u32| (n < 10).then_some(n))
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(parse_line(literal("abc"), "abc").unwrap(), "abc");
+        assert_eq!(parse_line(integer::<i32>(), "-42").unwrap(), -42);
+        assert_eq!(parse_line(identifier(), "x_1").unwrap(), "x_1");
+
+        let e = parse_line(integer::<u8>(), "256").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (1, "256"));
+        let e = parse_line(integer::<u8>(), "12ab").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (3, "ab"));
+        assert_eq!(e.expected, "end of line");
+        let e = parse_line(identifier(), "1x").unwrap_err();
+        assert_eq!(
+            (e.text.as_str(), e.expected.as_str()),
+            ("1", "an identifier")
+        );
+        let e = parse_line(identifier().labelled("a wire"), "").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "column 1: expected a wire, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_sequences() {
+        assert_eq!(parse_line(pair(), "3,4").unwrap(), (3, 4));
+        let e = parse_line(pair(), "3;4").unwrap_err();
+        assert_eq!(
+            (e.column, e.text.as_str(), e.expected.as_str()),
+            (2, ";", "`,`")
+        );
+
+        assert_eq!(parse_line(list(), "1, 2, 3").unwrap(), vec![1, 2, 3]);
+        let e = parse_line(list(), "1, 2, x").unwrap_err();
+        assert_eq!(
+            (e.column, e.text.as_str(), e.expected.as_str()),
+            (7, "x", "a number")
+        );
+    }
+
+    #[test]
+    fn test_alternatives() {
+        assert_eq!(parse_line(op(), "add 3").unwrap(), Op::Add(3));
+        assert_eq!(
+            parse_line(op(), "sub").unwrap(),
+            Op::Name("sub".to_string())
+        );
+
+        // `add` alone is an identifier, but the furthest failure is the
+        // missing number of the first alternative.
+        let e = parse_line(op(), "add x").unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (5, "a number"));
+        let e = parse_line(op(), "9").unwrap_err();
+        assert_eq!(e.expected, "`add ` or an identifier");
+    }
+
+    #[test]
+    fn test_try_map() {
+        assert_eq!(parse_line(digit(), "7").unwrap(), 7);
+        let e = parse_line(digit(), "12").unwrap_err();
+        assert_eq!(
+            (e.column, e.text.as_str(), e.expected.as_str()),
+            (1, "12", "a digit")
+        );
+    }
+}
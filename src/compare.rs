@@ -0,0 +1,267 @@
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    bench::format_duration,
+    registry::Entry,
+    runner::{self, Failure, Outcome},
+    solution::{Answer, Part, Unsolved},
+};
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Both parts of a day run on one of several inputs, such as those of each
+/// team member.
+pub struct InputRun {
+    pub path: PathBuf,
+    pub outcomes: Result<Vec<Outcome>>,
+}
+
+impl InputRun {
+    /// The input file name without its extension.
+    pub fn name(&self) -> String {
+        self.path.file_stem().map_or_else(
+            || self.path.display().to_string(),
+            |s| s.to_string_lossy().into_owned(),
+        )
+    }
+
+    fn failures(&self) -> Vec<&Failure> {
+        let failure = anyhow::Error::downcast_ref::<Failure>;
+        match &self.outcomes {
+            Ok(outcomes) => outcomes
+                .iter()
+                .filter_map(|o| o.answer.as_ref().err().and_then(failure))
+                .collect(),
+            Err(e) => failure(e).into_iter().collect(),
+        }
+    }
+
+    /// Whether the solver panicked or timed out on this input.
+    pub fn flagged(&self) -> bool {
+        !self.failures().is_empty()
+    }
+}
+
+fn run_one(entry: &'static Entry, path: &Path, timeout: Duration) -> Result<Vec<Outcome>> {
+    let input =
+        read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    runner::run_guarded(entry, input, timeout)
+}
+
+/// Runs `entry` on each input in turn, giving each `timeout` to finish.
+pub fn run(entry: &'static Entry, paths: &[PathBuf], timeout: Duration) -> Vec<InputRun> {
+    paths
+        .iter()
+        .map(|path| InputRun {
+            path: path.clone(),
+            outcomes: run_one(entry, path, timeout),
+        })
+        .collect()
+}
+
+fn error_cell(error: &anyhow::Error) -> String {
+    if error.is::<Unsolved>() {
+        return "unsolved".to_string();
+    }
+    match error.downcast_ref::<Failure>() {
+        Some(Failure::Panicked(message)) => format!("PANIC: {}", message),
+        Some(Failure::TimedOut(_)) => "TIMEOUT".to_string(),
+        None => format!("error: {:#}", error),
+    }
+}
+
+fn cell(answer: &Result<Answer>) -> String {
+    match answer {
+        Ok(Answer::Grid(_)) => "(grid, see below)".to_string(),
+        Ok(answer) => answer.to_string(),
+        Err(e) => error_cell(e),
+    }
+}
+
+/// Renders one row per input with the answers of both parts side by side,
+/// followed by any grid answers and a count of flagged inputs.
+pub fn render(runs: &[InputRun]) -> String {
+    let header = ["input", "part 1", "part 2", "time"].map(String::from);
+    let mut rows = Vec::new();
+    let mut grids = Vec::new();
+
+    for run in runs {
+        let name = match run.flagged() {
+            true => format!("{} !!", run.name()),
+            false => run.name(),
+        };
+        let row = match &run.outcomes {
+            Ok(outcomes) => {
+                let answer = |part: Part| {
+                    outcomes
+                        .iter()
+                        .find(|o| o.part == part)
+                        .map_or_else(|| "-".to_string(), |o| cell(&o.answer))
+                };
+                for outcome in outcomes {
+                    if let Ok(Answer::Grid(grid)) = &outcome.answer {
+                        grids.push(format!("{} part {}:\n{}", run.name(), outcome.part, grid));
+                    }
+                }
+                let elapsed: Duration = outcomes.iter().map(|o| o.elapsed).sum();
+                [
+                    name,
+                    answer(Part::One),
+                    answer(Part::Two),
+                    format_duration(elapsed),
+                ]
+            }
+            Err(e) => {
+                let error = error_cell(e);
+                [name, error.clone(), error, "-".to_string()]
+            }
+        };
+        rows.push(row);
+    }
+
+    let mut widths = header.clone().map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |row: &[String; 4]| {
+        format!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        )
+        .trim_end()
+        .to_string()
+    };
+
+    let mut out = vec![
+        line(&header),
+        "-".repeat(widths.iter().sum::<usize>() + 2 * 3),
+    ];
+    out.extend(rows.iter().map(line));
+    for grid in grids {
+        out.push(format!("\n{}", grid));
+    }
+    let failures: Vec<&Failure> = runs.iter().flat_map(InputRun::failures).collect();
+    let panicked = failures
+        .iter()
+        .filter(|f| matches!(f, Failure::Panicked(_)))
+        .count();
+    let flagged = runs.iter().filter(|r| r.flagged()).count();
+    out.push(format!(
+        "\n{} inputs, {} flagged ({} panicked, {} timed out)",
+        runs.len(),
+        flagged,
+        panicked,
+        failures.len() - panicked
+    ));
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::{input, registry};
+
+    fn outcome(part: Part, answer: Result<Answer>) -> Outcome {
+        Outcome {
+            part,
+            answer,
+            elapsed: Duration::from_millis(2),
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = env::temp_dir().join(format!("aoc_rust_compare_{}", std::process::id()));
+        let day = input::day_dir(&dir, 2017, 1);
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("alice.txt"), "1122").unwrap();
+        fs::write(day.join("bob.txt"), "91212129").unwrap();
+
+        let paths = input::day_inputs(&dir, 2017, 1).unwrap();
+        let entry = registry::find(2017, 1).unwrap();
+        let runs = run(entry, &paths, Duration::from_secs(5));
+        let answers: Vec<(String, Vec<String>)> = runs
+            .iter()
+            .map(|run| {
+                let outcomes = run.outcomes.as_ref().unwrap();
+                let answers = outcomes.iter().map(|o| cell(&o.answer)).collect();
+                (run.name(), answers)
+            })
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                ("alice".to_string(), vec!["3".to_string(), "0".to_string()]),
+                ("bob".to_string(), vec!["9".to_string(), "6".to_string()]),
+            ]
+        );
+        assert!(!runs.iter().any(InputRun::flagged));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let runs = vec![
+            InputRun {
+                path: PathBuf::from("inputs/Year2016/Day8/alice.txt"),
+                outcomes: Ok(vec![
+                    outcome(Part::One, Ok(Answer::Int(110))),
+                    outcome(Part::Two, Ok(Answer::Grid("#.\n.#".to_string()))),
+                ]),
+            },
+            InputRun {
+                path: PathBuf::from("inputs/Year2016/Day8/bob.txt"),
+                outcomes: Ok(vec![
+                    outcome(
+                        Part::One,
+                        Err(Failure::Panicked("index out of bounds".to_string()).into()),
+                    ),
+                    outcome(
+                        Part::Two,
+                        Err(Failure::TimedOut(Duration::from_secs(1)).into()),
+                    ),
+                ]),
+            },
+            InputRun {
+                path: PathBuf::from("inputs/Year2016/Day8/carol.txt"),
+                outcomes: Err(Failure::Panicked("bad input".to_string()).into()),
+            },
+        ];
+        let rendered = render(&runs);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            &lines[..5],
+            [
+                "input     part 1                      part 2               time",
+                "---------------------------------------------------------------",
+                "alice     110                         (grid, see below)  4.00ms",
+                "bob !!    PANIC: index out of bounds  TIMEOUT            4.00ms",
+                "carol !!  PANIC: bad input            PANIC: bad input        -",
+            ]
+        );
+        assert_eq!(&lines[6..8], ["alice part 2:", "#."]);
+        assert_eq!(
+            lines.last(),
+            Some(&"3 inputs, 2 flagged (2 panicked, 1 timed out)")
+        );
+    }
+}
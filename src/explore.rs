@@ -0,0 +1,234 @@
+use std::{fmt::Debug, str::FromStr};
+
+use anyhow::{anyhow, Result};
+
+#[cfg(feature = "year2015")]
+use crate::year2015;
+#[cfg(feature = "year2016")]
+use crate::year2016;
+#[cfg(all(feature = "year2018", feature = "regex"))]
+use crate::year2018;
+use crate::{
+    cli::render_error,
+    input::InputSource,
+    parse::ParseError,
+    registry::Entry,
+    runner,
+    solution::{Answer, Part},
+};
+
+/// A function of a day that can be called from `explore` on the lines of the
+/// session, such as evaluating one wire of a circuit.
+pub struct Helper {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+    pub run: fn(input: &str, args: &str) -> Result<String>,
+}
+
+/// What a day offers to `explore`: a parser for single lines of its input
+/// and any helpers.
+pub struct Explorer {
+    /// The name of the type lines are parsed into.
+    pub parses: &'static str,
+    pub parse_line: fn(&str) -> Result<String>,
+    pub helpers: &'static [Helper],
+}
+
+/// Parses `line` as a `T` and pretty-prints the result.
+pub fn describe<T>(line: &str) -> Result<String>
+where
+    T: FromStr<Err = ParseError> + Debug,
+{
+    Ok(format!("{:#?}", line.parse::<T>()?))
+}
+
+static EXPLORERS: &[(u16, u8, &Explorer)] = &[
+    #[cfg(feature = "year2015")]
+    (2015, 7, &year2015::day7::EXPLORER),
+    #[cfg(feature = "year2016")]
+    (2016, 8, &year2016::day8::EXPLORER),
+    #[cfg(all(feature = "year2018", feature = "regex"))]
+    (2018, 4, &year2018::day4::EXPLORER),
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Explorer> {
+    EXPLORERS
+        .iter()
+        .find(|&&(y, d, _)| (y, d) == (year, day))
+        .map(|&(_, _, explorer)| explorer)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reply {
+    Output(String),
+    Quit,
+}
+
+/// The state of an `explore` session: the day being explored and the lines
+/// pasted into it so far, which helpers and `:run` use as their input.
+pub struct Session {
+    entry: &'static Entry,
+    explorer: Option<&'static Explorer>,
+    lines: Vec<String>,
+}
+
+impl Session {
+    pub fn new(entry: &'static Entry) -> Self {
+        Session {
+            entry,
+            explorer: find(entry.year, entry.day),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn banner(&self) -> String {
+        let parses = match self.explorer {
+            Some(explorer) => format!("lines are parsed as {}", explorer.parses),
+            None => "this day has no line parser, lines are only collected".to_string(),
+        };
+        format!(
+            "exploring {} day {}, {}; :help lists the commands",
+            self.entry.year, self.entry.day, parses
+        )
+    }
+
+    fn help(&self) -> String {
+        let mut commands = vec![
+            (":help".to_string(), "show this help"),
+            (":lines".to_string(), "show the lines of the session"),
+            (":clear".to_string(), "forget the lines of the session"),
+            (
+                ":load".to_string(),
+                "replace the lines with the puzzle input",
+            ),
+            (":run".to_string(), "run both parts with the lines as input"),
+            (":quit".to_string(), "leave"),
+        ];
+        for helper in self.explorer.map_or(&[][..], |e| e.helpers) {
+            let usage = format!(":{} {}", helper.name, helper.usage);
+            commands.push((usage.trim_end().to_string(), helper.help));
+        }
+        let width = commands.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+        let lines: Vec<String> = commands
+            .iter()
+            .map(|(command, help)| format!("{:<width$}  {}", command, help))
+            .collect();
+        format!(
+            "{}\nanything else is parsed and added to the lines",
+            lines.join("\n")
+        )
+    }
+
+    fn input(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn run(&self) -> Result<String> {
+        let outcomes = runner::run(self.entry, &self.input(), &Part::ALL)?;
+        let lines: Vec<String> = outcomes
+            .iter()
+            .map(|outcome| match &outcome.answer {
+                Ok(Answer::Grid(grid)) => format!("part {}:\n{}", outcome.part, grid),
+                Ok(answer) => format!("part {}: {}", outcome.part, answer),
+                Err(e) => format!("part {}: error: {:#}", outcome.part, e),
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    fn command(&mut self, command: &str, args: &str, source: &InputSource) -> Result<String> {
+        match command {
+            "help" | "h" => Ok(self.help()),
+            "lines" => Ok(self.input()),
+            "clear" => {
+                self.lines.clear();
+                Ok("cleared".to_string())
+            }
+            "load" => {
+                let input = source.load(self.entry.year, self.entry.day)?;
+                self.lines = input.lines().map(String::from).collect();
+                Ok(format!("loaded {} lines", self.lines.len()))
+            }
+            "run" => self.run(),
+            name => {
+                let helper = self
+                    .explorer
+                    .and_then(|e| e.helpers.iter().find(|h| h.name == name))
+                    .ok_or_else(|| anyhow!("unknown command :{}, see :help", name))?;
+                (helper.run)(&self.input(), args)
+            }
+        }
+    }
+
+    /// Handles one line typed at the prompt.
+    pub fn handle(&mut self, line: &str, source: &InputSource) -> Reply {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() {
+            return Reply::Output(String::new());
+        }
+        let result = match line.trim().strip_prefix(':') {
+            Some("quit" | "q") => return Reply::Quit,
+            Some(command) => {
+                let (command, args) = command.split_once(' ').unwrap_or((command, ""));
+                self.command(command, args.trim(), source)
+            }
+            None => match self.explorer {
+                Some(explorer) => {
+                    (explorer.parse_line)(line).inspect(|_| self.lines.push(line.to_string()))
+                }
+                None => {
+                    self.lines.push(line.to_string());
+                    Ok(format!("added line {}", self.lines.len()))
+                }
+            },
+        };
+        Reply::Output(result.unwrap_or_else(|e| render_error(&e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registry;
+
+    fn output(session: &mut Session, line: &str) -> String {
+        match session.handle(line, &InputSource::Literal("123 -> a".to_string())) {
+            Reply::Output(output) => output,
+            Reply::Quit => panic!("{} quit the session", line),
+        }
+    }
+
+    #[test]
+    fn test_session() {
+        let mut session = Session::new(registry::find(2015, 7).unwrap());
+        assert!(session.banner().contains("parsed as Instruction"));
+        assert!(output(&mut session, ":help").contains(":wire <wire>"));
+
+        assert!(output(&mut session, "123 -> x").contains("Assign"));
+        assert!(output(&mut session, "x LSHIFT 2 -> y").contains("LShift"));
+        let error = output(&mut session, "x LSHIFT two -> z");
+        assert!(error.starts_with("error: column 10: expected a number"));
+        assert_eq!(output(&mut session, ":lines"), "123 -> x\nx LSHIFT 2 -> y");
+        assert_eq!(output(&mut session, ":wire y"), "y = 492");
+        assert!(output(&mut session, ":wire q").starts_with("error:"));
+        assert!(output(&mut session, ":frobnicate").contains("unknown command"));
+
+        assert_eq!(output(&mut session, ":load"), "loaded 1 lines");
+        assert_eq!(output(&mut session, ":run"), "part 1: 123\npart 2: 123");
+        assert_eq!(output(&mut session, ":clear"), "cleared");
+        assert_eq!(output(&mut session, ":lines"), "");
+        assert_eq!(
+            session.handle(":quit", &InputSource::default()),
+            Reply::Quit
+        );
+    }
+
+    #[test]
+    fn test_session_without_explorer() {
+        let mut session = Session::new(registry::find(2017, 1).unwrap());
+        assert!(session.banner().contains("no line parser"));
+        assert_eq!(output(&mut session, "1122"), "added line 1");
+        assert_eq!(output(&mut session, ":run"), "part 1: 3\npart 2: 0");
+    }
+}
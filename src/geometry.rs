@@ -0,0 +1,369 @@
+use std::{
+    fmt,
+    iter::successors,
+    ops::{Add, AddAssign, Sub},
+    str::FromStr,
+};
+
+use crate::parse::ParseError;
+
+/// Integer types that can be used as the coordinates of a [`Point`].
+pub trait Coordinate: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_i128(self) -> i128;
+
+    fn from_i128(n: i128) -> Option<Self>;
+}
+
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(n: i128) -> Option<Self> {
+                    Self::try_from(n).ok()
+                }
+            }
+        )*
+    };
+}
+
+coordinate!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+fn abs_diff<T: Coordinate>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// A point on a grid. `y` grows downwards, as rows of puzzle input do, so
+/// moving [`Direction::North`] decreases it.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Coordinate> Point<T> {
+    pub const ORIGIN: Self = Point::new(T::ZERO, T::ZERO);
+
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// The point `n` steps away in `direction`, or `None` if it does not fit
+    /// in `T`, such as stepping west of `x == 0` with unsigned coordinates.
+    pub fn checked_moved(self, direction: Direction, n: T) -> Option<Self> {
+        let (dx, dy) = direction.delta();
+        let n = n.to_i128();
+        Some(Point {
+            x: T::from_i128(self.x.to_i128() + dx as i128 * n)?,
+            y: T::from_i128(self.y.to_i128() + dy as i128 * n)?,
+        })
+    }
+
+    pub fn moved(self, direction: Direction, n: T) -> Self {
+        self.checked_moved(direction, n)
+            .expect("point moved out of the range of its coordinates")
+    }
+
+    pub fn checked_step(self, direction: Direction) -> Option<Self> {
+        self.checked_moved(direction, T::ONE)
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self.moved(direction, T::ONE)
+    }
+
+    /// The points passed when walking from this one in `direction`, not
+    /// including this one.
+    pub fn walk(self, direction: Direction) -> impl Iterator<Item = Self> {
+        successors(Some(self), move |p| p.checked_step(direction)).skip(1)
+    }
+
+    /// The up to four points sharing an edge with this one, clockwise from
+    /// the north.
+    pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |d| self.checked_step(d))
+    }
+
+    /// The up to eight points sharing an edge or corner with this one,
+    /// clockwise from the north.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.checked_step(d))
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Coordinate> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A compass direction, numbered clockwise in eighths of a turn from north.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The change in `x` and `y` of one step in this direction.
+    pub const fn delta(self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::East => (1, 0),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (0, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as u8 % 2 == 1
+    }
+
+    /// Rotates clockwise by `eighths` eighths of a turn, anticlockwise when
+    /// negative.
+    pub fn rotate(self, eighths: i32) -> Self {
+        Direction::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.rotate(-2),
+            Turn::Right => self.rotate(2),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Parses the `^v<>` arrow notation.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::North),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            '>' => Some(Direction::East),
+            _ => None,
+        }
+    }
+
+    /// Parses the `UDLR` up/down/left/right notation.
+    pub fn from_udlr(c: char) -> Option<Self> {
+        match c {
+            'U' => Some(Direction::North),
+            'D' => Some(Direction::South),
+            'L' => Some(Direction::West),
+            'R' => Some(Direction::East),
+            _ => None,
+        }
+    }
+
+    /// Parses the `NSEW` compass notation.
+    pub fn from_compass(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(Direction::North),
+            'S' => Some(Direction::South),
+            'E' => Some(Direction::East),
+            'W' => Some(Direction::West),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Turn {
+    /// Parses the `L/R` notation.
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'L' => Some(Turn::Left),
+            'R' => Some(Turn::Right),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Turn {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next().and_then(Turn::from_letter), chars.next()) {
+            (Some(turn), None) => Ok(turn),
+            _ => Err(ParseError::new(s, s, "L or R")),
+        }
+    }
+}
+
+/// Parses every character of `line` with `parse`, such as
+/// [`Direction::from_arrow`], pointing any error at the offending character.
+pub fn parse_chars<T>(
+    line: &str,
+    expected: &str,
+    parse: impl Fn(char) -> Option<T>,
+) -> Result<Vec<T>, ParseError> {
+    line.char_indices()
+        .map(|(i, c)| {
+            parse(c).ok_or_else(|| ParseError::new(line, &line[i..i + c.len_utf8()], expected))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(1, -2), Point::new(-3, 4));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(Point::new(5u8, 1).manhattan(Point::new(2, 3)), 5);
+    }
+
+    #[test]
+    fn test_moves() {
+        let p = Point::new(2, 3);
+        assert_eq!(p.step(Direction::North), Point::new(2, 2));
+        assert_eq!(p.step(Direction::SouthWest), Point::new(1, 4));
+        assert_eq!(p.moved(Direction::East, 5), Point::new(7, 3));
+        assert_eq!(
+            p.walk(Direction::West).take(3).collect::<Vec<_>>(),
+            vec![Point::new(1, 3), Point::new(0, 3), Point::new(-1, 3)]
+        );
+        assert_eq!(Point::new(0usize, 1).checked_step(Direction::West), None);
+        assert_eq!(Point::new(1u8, 0).walk(Direction::West).count(), 1);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let origin = Point::<u32>::ORIGIN;
+        assert_eq!(
+            origin.neighbours4().collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(origin.neighbours8().count(), 3);
+        assert_eq!(Point::new(5, 5).neighbours8().count(), 8);
+    }
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+        assert_eq!(Direction::West.turn(Turn::Right), Direction::North);
+        assert_eq!(Direction::NorthEast.rotate(-3), Direction::West);
+        assert_eq!(Direction::SouthEast.opposite(), Direction::NorthWest);
+        assert!(Direction::SouthWest.is_diagonal());
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn(Turn::Left).turn(Turn::Right), direction);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse_chars("^>v<", "an arrow", Direction::from_arrow).unwrap(),
+            Direction::ORTHOGONAL
+        );
+        assert_eq!(
+            parse_chars("URDL", "one of UDLR", Direction::from_udlr).unwrap(),
+            Direction::ORTHOGONAL
+        );
+        assert_eq!(
+            parse_chars("NESW", "one of NSEW", Direction::from_compass).unwrap(),
+            Direction::ORTHOGONAL
+        );
+        let error = parse_chars("UDxL", "one of UDLR", Direction::from_udlr).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "x"));
+
+        assert_eq!("L".parse::<Turn>().unwrap(), Turn::Left);
+        assert!("LR".parse::<Turn>().is_err());
+        assert_eq!(Turn::from_letter('R'), Some(Turn::Right));
+    }
+}
@@ -0,0 +1,440 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+pub type NodeId = usize;
+
+/// The most nodes [`Graph::hamiltonian_path`] and
+/// [`Graph::hamiltonian_cycle`] will search: their tables grow as 2^n n, so
+/// this keeps them to a few tens of megabytes.
+pub const MAX_ROUTE_NODES: usize = 16;
+
+/// A weighted graph whose nodes are named, such as cities, and referred to
+/// by the [`NodeId`] each name is interned as.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    edges: Vec<Vec<(NodeId, i64)>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Graph::default()
+    }
+
+    /// Builds a graph from `(from, to, weight)` edges that can be travelled
+    /// both ways.
+    pub fn undirected<'a>(edges: impl IntoIterator<Item = (&'a str, &'a str, i64)>) -> Self {
+        let mut graph = Graph::new();
+        for (a, b, weight) in edges {
+            let (a, b) = (graph.node(a), graph.node(b));
+            graph.add_edge(a, b, weight);
+            graph.add_edge(b, a, weight);
+        }
+        graph
+    }
+
+    /// The id of the node called `name`, adding the node if it is new.
+    pub fn node(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Adds an edge that can only be travelled from `from` to `to`.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: i64) {
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = (NodeId, i64)> + '_ {
+        self.edges[id].iter().copied()
+    }
+
+    /// The weight of the lightest edge from `from` to `to`.
+    pub fn weight(&self, from: NodeId, to: NodeId) -> Option<i64> {
+        self.neighbours(from)
+            .filter(|&(id, _)| id == to)
+            .map(|(_, weight)| weight)
+            .min()
+    }
+
+    /// The names of the nodes of `route`, in order.
+    pub fn names<'a>(&'a self, route: &'a Route) -> impl Iterator<Item = &'a str> {
+        route.nodes.iter().map(|&id| self.name(id))
+    }
+
+    /// The best path visiting every node once, starting and ending anywhere.
+    ///
+    /// Uses Held-Karp dynamic programming, which takes O(2^n n^2) time, and
+    /// returns `None` for graphs of more than [`MAX_ROUTE_NODES`] nodes.
+    pub fn hamiltonian_path(&self, objective: Objective) -> Option<Route> {
+        self.held_karp(objective, false)
+    }
+
+    /// The best cycle visiting every node once and returning to the first.
+    /// The returned route starts at node 0 and does not repeat it at the end,
+    /// but its length includes the way back. A single node is a cycle of
+    /// length 0 on its own.
+    pub fn hamiltonian_cycle(&self, objective: Objective) -> Option<Route> {
+        self.held_karp(objective, true)
+    }
+
+    fn held_karp(&self, objective: Objective, cycle: bool) -> Option<Route> {
+        let n = self.len();
+        if n == 0 || n > MAX_ROUTE_NODES {
+            return None;
+        }
+        let weights: Vec<Vec<Option<i64>>> = (0..n)
+            .map(|from| (0..n).map(|to| self.weight(from, to)).collect())
+            .collect();
+        let full = (1usize << n) - 1;
+
+        // best[set][last] is the length of the best path through the nodes
+        // of `set` ending at `last`, and parent[set][last] its node before.
+        let mut best = vec![vec![None; n]; full + 1];
+        let mut parent = vec![vec![usize::MAX; n]; full + 1];
+        let starts = if cycle { 0..1 } else { 0..n };
+        for start in starts {
+            best[1 << start][start] = Some(0);
+        }
+        for set in 1..=full {
+            for last in 0..n {
+                let Some(length) = best[set][last] else {
+                    continue;
+                };
+                for next in (0..n).filter(|&next| set & (1 << next) == 0) {
+                    let Some(weight) = weights[last][next] else {
+                        continue;
+                    };
+                    let candidate = length + weight;
+                    let cell = &mut best[set | 1 << next][next];
+                    if cell.is_none_or(|current| objective.better(candidate, current)) {
+                        *cell = Some(candidate);
+                        parent[set | 1 << next][next] = last;
+                    }
+                }
+            }
+        }
+
+        let (mut last, length) = (0..n)
+            .filter_map(|last| {
+                let back = if cycle && n > 1 { weights[last][0]? } else { 0 };
+                Some((last, best[full][last]? + back))
+            })
+            .reduce(|a, b| if objective.better(b.1, a.1) { b } else { a })?;
+        let mut nodes = Vec::with_capacity(n);
+        let mut set = full;
+        while last != usize::MAX {
+            nodes.push(last);
+            let previous = parent[set][last];
+            set &= !(1 << last);
+            last = previous;
+        }
+        nodes.reverse();
+        Some(Route { nodes, length })
+    }
+
+    /// The fewest edges from `start` to every node it can reach.
+    pub fn bfs(&self, start: NodeId) -> Search<NodeId, usize> {
+        bfs(start, |&id| self.neighbours(id).map(|(next, _)| next))
+    }
+
+    /// The lightest paths from `start` to every node it can reach.
+    ///
+    /// Panics if an edge on the way has a negative weight.
+    pub fn dijkstra(&self, start: NodeId) -> Search<NodeId, i64> {
+        dijkstra(start, |&id| {
+            self.neighbours(id).inspect(move |&(next, weight)| {
+                assert!(
+                    weight >= 0,
+                    "dijkstra needs non-negative weights, {} to {} weighs {}",
+                    self.name(id),
+                    self.name(next),
+                    weight
+                )
+            })
+        })
+    }
+}
+
+/// Whether a route should be as short or as long as possible.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Objective {
+    Min,
+    Max,
+}
+
+impl Objective {
+    fn better(self, a: i64, b: i64) -> bool {
+        match self {
+            Objective::Min => a < b,
+            Objective::Max => a > b,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Route {
+    pub nodes: Vec<NodeId>,
+    pub length: i64,
+}
+
+/// The result of a search from one node: the cost of reaching every node it
+/// reached and the node each one was reached from.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    pub costs: HashMap<N, C>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash, C> Search<N, C> {
+    pub fn cost(&self, node: &N) -> Option<&C> {
+        self.costs.get(node)
+    }
+
+    /// The nodes on the way from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.costs.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from `start` over the graph given by `neighbours`,
+/// counting the steps to each node reached.
+pub fn bfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search {
+        costs: HashMap::from([(start.clone(), 0)]),
+        parents: HashMap::new(),
+    };
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let steps = search.costs[&node] + 1;
+        for next in neighbours(&node) {
+            if let Entry::Vacant(entry) = search.costs.entry(next.clone()) {
+                entry.insert(steps);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+/// A node waiting in the Dijkstra queue, ordered so the cheapest comes out of
+/// the max-heap first.
+struct Queued<N, C>(C, N);
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+/// Dijkstra's algorithm from `start` over the graph given by `neighbours`,
+/// which yields each neighbour with the non-negative cost of moving to it.
+/// `C::default()` is the cost of staying put.
+pub fn dijkstra<N, C, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search {
+        costs: HashMap::from([(start.clone(), C::default())]),
+        parents: HashMap::new(),
+    };
+    let mut queue = BinaryHeap::from([Queued(C::default(), start)]);
+    while let Some(Queued(cost, node)) = queue.pop() {
+        if search.costs[&node] < cost {
+            // A cheaper way here was found after this one was queued.
+            continue;
+        }
+        for (next, step) in neighbours(&node) {
+            let candidate = cost + step;
+            if search
+                .costs
+                .get(&next)
+                .is_none_or(|&known| candidate < known)
+            {
+                search.costs.insert(next.clone(), candidate);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push(Queued(candidate, next));
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cities() -> Graph {
+        Graph::undirected([
+            ("London", "Dublin", 464),
+            ("London", "Belfast", 518),
+            ("Dublin", "Belfast", 141),
+        ])
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut graph = cities();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.id("Belfast"), Some(2));
+        assert_eq!(graph.node("Dublin"), 1);
+        assert_eq!(graph.node("Paris"), 3);
+        assert_eq!(graph.name(3), "Paris");
+        assert_eq!(graph.weight(2, 0), Some(518));
+        assert_eq!(graph.weight(0, 3), None);
+    }
+
+    #[test]
+    fn test_hamiltonian_path() {
+        let graph = cities();
+        let shortest = graph.hamiltonian_path(Objective::Min).unwrap();
+        assert_eq!(shortest.length, 605);
+        assert_eq!(
+            graph.names(&shortest).collect::<Vec<_>>(),
+            vec!["Belfast", "Dublin", "London"]
+        );
+        let longest = graph.hamiltonian_path(Objective::Max).unwrap();
+        assert_eq!(longest.length, 982);
+        assert_eq!(
+            graph.names(&longest).collect::<Vec<_>>(),
+            vec!["Belfast", "London", "Dublin"]
+        );
+
+        let mut disconnected = cities();
+        disconnected.node("Paris");
+        assert_eq!(disconnected.hamiltonian_path(Objective::Min), None);
+        assert_eq!(Graph::new().hamiltonian_path(Objective::Min), None);
+    }
+
+    #[test]
+    fn test_hamiltonian_cycle() {
+        let graph = Graph::undirected([
+            ("a", "b", 1),
+            ("b", "c", 1),
+            ("c", "d", 1),
+            ("d", "a", 1),
+            ("a", "c", 5),
+            ("b", "d", 5),
+        ]);
+        let shortest = graph.hamiltonian_cycle(Objective::Min).unwrap();
+        assert_eq!(shortest.length, 4);
+        assert_eq!(shortest.nodes.len(), 4);
+        assert_eq!(shortest.nodes[0], 0);
+        assert_eq!(graph.hamiltonian_cycle(Objective::Max).unwrap().length, 12);
+
+        let mut single = Graph::new();
+        single.node("a");
+        let route = single.hamiltonian_cycle(Objective::Min).unwrap();
+        assert_eq!((route.nodes, route.length), (vec![0], 0));
+    }
+
+    #[test]
+    fn test_route_node_limit() {
+        let names: Vec<String> = (0..=MAX_ROUTE_NODES).map(|i| i.to_string()).collect();
+        let chain =
+            |len: usize| Graph::undirected(names[..len].windows(2).map(|w| (&*w[0], &*w[1], 1)));
+        let route = chain(MAX_ROUTE_NODES).hamiltonian_path(Objective::Min);
+        assert_eq!(route.unwrap().length, MAX_ROUTE_NODES as i64 - 1);
+        let too_big = chain(MAX_ROUTE_NODES + 1);
+        assert_eq!(too_big.hamiltonian_path(Objective::Min), None);
+        assert_eq!(too_big.hamiltonian_cycle(Objective::Min), None);
+    }
+
+    #[test]
+    fn test_bfs() {
+        let mut graph = Graph::new();
+        let (a, b, c, d) = (
+            graph.node("a"),
+            graph.node("b"),
+            graph.node("c"),
+            graph.node("d"),
+        );
+        graph.add_edge(a, b, 1);
+        graph.add_edge(b, c, 1);
+        graph.add_edge(a, c, 9);
+        graph.add_edge(d, a, 1);
+
+        let search = graph.bfs(a);
+        assert_eq!(search.cost(&c), Some(&1));
+        assert_eq!(search.cost(&d), None);
+        assert_eq!(search.path_to(&c), Some(vec![a, c]));
+
+        let search = graph.dijkstra(a);
+        assert_eq!(search.cost(&c), Some(&2));
+        assert_eq!(search.path_to(&c), Some(vec![a, b, c]));
+        assert_eq!(search.path_to(&d), None);
+    }
+
+    #[test]
+    fn test_implicit_graph() {
+        // Steps of one or three along a line, where landing on a multiple of
+        // five costs ten.
+        let search = dijkstra(0i32, |&n| {
+            [n + 1, n + 3]
+                .into_iter()
+                .filter(|&m| m <= 12)
+                .map(|m| (m, if m % 5 == 0 { 10 } else { 1 }))
+        });
+        assert_eq!(search.cost(&12), Some(&4));
+        assert_eq!(search.path_to(&12), Some(vec![0, 3, 6, 9, 12]));
+        assert_eq!(
+            bfs(0i32, |&n| [n + 2].into_iter().filter(|&m| m <= 6))
+                .costs
+                .len(),
+            4
+        );
+    }
+}
@@ -0,0 +1,490 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{self, Point},
+    parse::ParseError,
+};
+
+/// A rectangle of cells: `width` columns and `height` rows starting at
+/// `origin`, its top left corner.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Rect {
+    pub origin: Point<usize>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub const fn new(origin: Point<usize>, width: usize, height: usize) -> Self {
+        Rect {
+            origin,
+            width,
+            height,
+        }
+    }
+
+    /// The rectangle with `a` and `b` as opposite corners, both included.
+    pub fn spanning(a: Point<usize>, b: Point<usize>) -> Self {
+        let origin = Point::new(a.x.min(b.x), a.y.min(b.y));
+        Rect::new(
+            origin,
+            a.x.max(b.x) - origin.x + 1,
+            a.y.max(b.y) - origin.y + 1,
+        )
+    }
+
+    /// The point just past the bottom right corner.
+    fn end(&self) -> Point<usize> {
+        Point::new(self.origin.x + self.width, self.origin.y + self.height)
+    }
+}
+
+/// A dense rectangular grid of cells, stored row by row and indexed by
+/// [`Point`]s with `y` counting rows down from the top.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Sets every cell of `rect` to `value`.
+    ///
+    /// Panics if `rect` does not fit in the grid.
+    pub fn fill_rect(&mut self, rect: Rect, value: T) {
+        self.map_rect(rect, |_| value.clone());
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point<usize>) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point<usize>) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn get_mut(&mut self, point: Point<usize>) -> Option<&mut T> {
+        self.contains(point)
+            .then(|| &mut self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a chunk size of 0, and a grid without columns has
+        // no cells to show anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// The cells row by row.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The points of the grid row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// The cells row by row along with their points.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    fn check_rect(&self, rect: Rect) {
+        let end = rect.end();
+        assert!(
+            end.x <= self.width && end.y <= self.height,
+            "{:?} does not fit in a {}x{} grid",
+            rect,
+            self.width,
+            self.height
+        );
+    }
+
+    /// The cells of `rect` row by row.
+    ///
+    /// Panics if `rect` does not fit in the grid.
+    pub fn rect(&self, rect: Rect) -> impl Iterator<Item = &T> {
+        self.check_rect(rect);
+        let (start, end) = (rect.origin, rect.end());
+        (start.y..end.y).flat_map(move |y| &self.row(y)[start.x..end.x])
+    }
+
+    /// Replaces every cell of `rect` with `f` of its value.
+    ///
+    /// Panics if `rect` does not fit in the grid.
+    pub fn map_rect(&mut self, rect: Rect, mut f: impl FnMut(&T) -> T) {
+        self.check_rect(rect);
+        for y in rect.origin.y..rect.end().y {
+            let start = y * self.width;
+            for cell in &mut self.cells[start + rect.origin.x..start + rect.end().x] {
+                *cell = f(cell);
+            }
+        }
+    }
+
+    /// Shifts row `y` right by `by` cells, wrapping around.
+    pub fn rotate_row(&mut self, y: usize, by: usize) {
+        assert!(y < self.height, "row {} is outside the grid", y);
+        let start = y * self.width;
+        self.cells[start..start + self.width].rotate_right(by % self.width.max(1));
+    }
+
+    /// Shifts column `x` down by `by` cells, wrapping around.
+    pub fn rotate_column(&mut self, x: usize, by: usize) {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        let by = by % self.height.max(1);
+        // Rotating is reversing the whole column and then both of its parts.
+        let mut reverse = |mut top: usize, mut bottom: usize| {
+            while top + 1 < bottom {
+                bottom -= 1;
+                self.cells
+                    .swap(top * self.width + x, bottom * self.width + x);
+                top += 1;
+            }
+        };
+        reverse(0, self.height);
+        reverse(0, by);
+        reverse(by, self.height);
+    }
+
+    /// Parses one cell per character of each line with `parse`, such as one
+    /// mapping `#` and `.` to `true` and `false`. Every line must be as long
+    /// as the first.
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        parse: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut grid = Grid {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        };
+        for (n, line) in input.lines().enumerate() {
+            let row =
+                geometry::parse_chars(line, expected, &parse).map_err(|e| e.at_line(n + 1))?;
+            if n == 0 {
+                grid.width = row.len();
+            } else if row.len() != grid.width {
+                let error = format!("a row of {} cells like the first", grid.width);
+                return Err(ParseError::new(line, line, error).at_line(n + 1));
+            }
+            grid.cells.extend(row);
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    /// Renders the grid with `glyph` drawing each cell.
+    pub fn display<F: Fn(&T) -> char>(&self, glyph: F) -> Glyphs<'_, T, F> {
+        Glyphs { grid: self, glyph }
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point<usize>) -> &T {
+        let (width, height) = (self.width, self.height);
+        self.get(point)
+            .unwrap_or_else(|| panic!("{} is outside the {}x{} grid", point, width, height))
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, point: Point<usize>) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{} is outside the {}x{} grid", point, width, height))
+    }
+}
+
+/// A grid rendered with a glyph per cell, see [`Grid::display`].
+pub struct Glyphs<'a, T, F> {
+    grid: &'a Grid<T>,
+    glyph: F,
+}
+
+impl<T, F: Fn(&T) -> char> fmt::Display for Glyphs<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (self.glyph)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A grid without bounds, storing only the cells that were set and reading
+/// every other cell as a default value.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point<i32>, T>,
+    default: T,
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new(T::default())
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+        }
+    }
+
+    /// The value of a cell, or the default if it was never set.
+    pub fn get(&self, point: Point<i32>) -> &T {
+        self.cells.get(&point).unwrap_or(&self.default)
+    }
+
+    pub fn insert(&mut self, point: Point<i32>, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    /// Whether the cell was set.
+    pub fn contains(&self, point: Point<i32>) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// The number of cells that were set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The cells that were set along with their points, in no particular
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<i32>, &T)> {
+        self.cells.iter().map(|(&point, value)| (point, value))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// The top left and bottom right corners of the smallest rectangle
+    /// holding every cell that was set.
+    pub fn bounds(&self) -> Option<(Point<i32>, Point<i32>)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        }))
+    }
+
+    /// Draws the cells within [`bounds`](Self::bounds) row by row, with
+    /// `glyph` getting `None` for the cells that were never set.
+    pub fn render(&self, glyph: impl Fn(Point<i32>, Option<&T>) -> char) -> String {
+        let Some((min, max)) = self.bounds() else {
+            return String::new();
+        };
+        let rows: Vec<String> = (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| {
+                        let point = Point::new(x, y);
+                        glyph(point, self.cells.get(&point))
+                    })
+                    .collect()
+            })
+            .collect();
+        rows.join("\n")
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// The value of a cell to change, set to the default first if needed.
+    pub fn get_mut(&mut self, point: Point<i32>) -> &mut T {
+        self.cells
+            .entry(point)
+            .or_insert_with(|| self.default.clone())
+    }
+}
+
+impl SparseGrid<usize> {
+    /// Counts a visit to `point`, returning how many times it was visited.
+    pub fn visit(&mut self, point: Point<i32>) -> usize {
+        let visits = self.get_mut(point);
+        *visits += 1;
+        *visits
+    }
+}
+
+/// Counts the visits to each point.
+impl FromIterator<Point<i32>> for SparseGrid<usize> {
+    fn from_iter<I: IntoIterator<Item = Point<i32>>>(points: I) -> Self {
+        let mut grid = SparseGrid::default();
+        for point in points {
+            grid.visit(point);
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn glyph(lit: &bool) -> char {
+        if *lit {
+            '#'
+        } else {
+            '.'
+        }
+    }
+
+    fn lights(input: &str) -> Grid<bool> {
+        Grid::parse(input, "# or .", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_indexing() {
+        let mut grid = Grid::new(3, 2, 0);
+        grid[Point::new(2, 1)] = 5;
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&5));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 2)), None);
+        assert_eq!(grid.row(1), [0, 0, 5]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![0, 5]);
+        assert_eq!(
+            grid.iter().find(|&(_, &v)| v == 5).map(|(p, _)| p),
+            Some(Point::new(2, 1))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "3,0 is outside the 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let grid = Grid::new(3, 2, 0);
+        let _ = grid[Point::new(3, 0)];
+    }
+
+    #[test]
+    fn test_rects() {
+        let mut grid = Grid::new(4, 3, 0);
+        grid.fill_rect(Rect::new(Point::new(1, 0), 2, 2), 1);
+        grid.map_rect(Rect::spanning(Point::new(3, 2), Point::new(2, 1)), |n| {
+            n + 2
+        });
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![[0, 1, 1, 0], [0, 1, 3, 2], [0, 0, 2, 2]]
+        );
+        assert_eq!(
+            grid.rect(Rect::new(Point::new(1, 1), 2, 2))
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![1, 3, 0, 2]
+        );
+        grid.fill_rect(Rect::new(Point::ORIGIN, 0, 3), 9);
+        assert_eq!(grid.values().filter(|&&n| n == 9).count(), 0);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let mut grid = lights("###....\n###....\n.......");
+        grid.rotate_column(1, 1);
+        grid.rotate_row(0, 4);
+        grid.rotate_column(1, 4);
+        assert_eq!(grid.display(glyph).to_string(), ".#..#.#\n#.#....\n.#.....");
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = lights("#.\n.#\n");
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert!(grid[Point::new(1, 1)]);
+
+        let error = Grid::parse("#.\n#x", "# or .", |c| (c == '#').then_some(())).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), 2));
+        let error = Grid::parse("#.\n#", "#", |c| Some(c == '#')).unwrap_err();
+        assert_eq!(error.line, Some(2));
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.render(|_, _| '?'), "");
+
+        grid.insert(Point::new(-2, 1), 'a');
+        *grid.get_mut(Point::new(1, -1)) = 'b';
+        assert_eq!(*grid.get(Point::new(0, 0)), '.');
+        assert_eq!(*grid.get(Point::new(-2, 1)), 'a');
+        assert!(!grid.contains(Point::new(0, 0)));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some((Point::new(-2, -1), Point::new(1, 1))));
+        assert_eq!(
+            grid.render(|point, cell| match cell {
+                Some(&c) => c,
+                None if point == Point::ORIGIN => 'o',
+                None => ' ',
+            }),
+            "   b\n  o \na   "
+        );
+    }
+
+    #[test]
+    fn test_visits() {
+        let mut grid: SparseGrid<usize> = [Point::ORIGIN, Point::new(0, 1), Point::ORIGIN]
+            .into_iter()
+            .collect();
+        assert_eq!(*grid.get(Point::ORIGIN), 2);
+        assert_eq!(grid.visit(Point::new(0, 1)), 2);
+        assert_eq!(grid.visit(Point::new(5, 5)), 1);
+        assert_eq!(*grid.get(Point::new(4, 4)), 0);
+    }
+}
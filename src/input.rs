@@ -0,0 +1,140 @@
+use std::{
+    env,
+    fs::{self, read_to_string},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+/// Environment variable pointing at an alternative inputs directory.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+/// Where the puzzle input for a day comes from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum InputSource {
+    /// A directory laid out as `YearXXXX/DayN.txt`.
+    Directory(PathBuf),
+    /// A single file, used as-is for whichever day is being run.
+    File(PathBuf),
+    Stdin,
+    Literal(String),
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Directory(default_dir())
+    }
+}
+
+/// The inputs directory, honouring [`INPUT_DIR_VAR`] when it is set.
+pub fn default_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR))
+}
+
+pub fn day_path(dir: &Path, year: u16, day: u8) -> PathBuf {
+    dir.join(format!("Year{}", year))
+        .join(format!("Day{}.txt", day))
+}
+
+/// The directory holding several inputs of a day, such as one per team
+/// member, laid out as `YearXXXX/DayN/*.txt`.
+pub fn day_dir(dir: &Path, year: u16, day: u8) -> PathBuf {
+    dir.join(format!("Year{}", year))
+        .join(format!("Day{}", day))
+}
+
+/// The `.txt` files in the [`day_dir`] of a day, sorted by name.
+pub fn day_inputs(dir: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>> {
+    let dir = day_dir(dir, year, day);
+    let entries = fs::read_dir(&dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .with_context(|| format!("could not read {}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries
+        .into_iter()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "txt"))
+        .collect();
+    if paths.is_empty() {
+        bail!("no .txt inputs in {}", dir.display());
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+impl InputSource {
+    pub fn load(&self, year: u16, day: u8) -> Result<String> {
+        match self {
+            InputSource::Directory(dir) => read_file(&day_path(dir, year, day)),
+            InputSource::File(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("could not read input from stdin")?;
+                Ok(input)
+            }
+            InputSource::Literal(input) => Ok(input.clone()),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    read_to_string(path).with_context(|| format!("could not read {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_day_path() {
+        assert_eq!(
+            day_path(Path::new("inputs"), 2015, 7),
+            PathBuf::from("inputs/Year2015/Day7.txt")
+        );
+    }
+
+    #[test]
+    fn test_day_inputs() {
+        let dir = env::temp_dir().join(format!("aoc_rust_day_inputs_{}", std::process::id()));
+        let day = day_dir(&dir, 2015, 7);
+        fs::create_dir_all(&day).unwrap();
+        for name in ["bob.txt", "alice.txt", "notes.md"] {
+            fs::write(day.join(name), "123 -> a").unwrap();
+        }
+
+        assert_eq!(
+            day_inputs(&dir, 2015, 7).unwrap(),
+            vec![day.join("alice.txt"), day.join("bob.txt")]
+        );
+        assert!(day_inputs(&dir, 2015, 8).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_literal() {
+        let source = InputSource::Literal("1113122113".to_string());
+        assert_eq!(source.load(2015, 10).unwrap(), "1113122113");
+    }
+
+    #[test]
+    fn test_load_directory() {
+        let dir = env::temp_dir().join(format!("aoc_rust_input_{}", std::process::id()));
+        fs::create_dir_all(dir.join("Year2017")).unwrap();
+        fs::write(dir.join("Year2017/Day3.txt"), "347991").unwrap();
+
+        let source = InputSource::Directory(dir.clone());
+        assert_eq!(source.load(2017, 3).unwrap(), "347991");
+        assert!(source.load(2017, 4).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,2 +1,85 @@
-#![feature(iter_array_chunks)]
+#![cfg_attr(feature = "year2016", feature(iter_array_chunks))]
+pub mod alloc;
+pub mod answers;
+pub mod bench;
+pub mod cli;
+#[cfg(feature = "fetch")]
+pub mod client;
+pub mod combinator;
+pub mod compare;
+pub mod explore;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod log;
+pub mod parse;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod status;
+pub mod watch;
+#[cfg(feature = "year2015")]
+pub mod year2015;
+#[cfg(feature = "year2016")]
 pub mod year2016;
+#[cfg(feature = "year2017")]
+pub mod year2017;
+#[cfg(feature = "year2018")]
+pub mod year2018;
+
+use anyhow::{anyhow, Result};
+
+pub use solution::{Answer, Part};
+
+/// Solves one part of a day with `input` as the puzzle input.
+pub fn solve_str(year: u16, day: u8, part: Part, input: &str) -> Result<Answer> {
+    let entry = registry::find(year, day)
+        .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?;
+    let parsed = entry.solution.parse_dyn(input)?;
+    entry.solution.part_dyn(&*parsed, part)
+}
+
+/// The (year, day) pairs [`solve_str`] can solve, in order. Which years are
+/// included depends on the enabled features.
+pub fn available() -> Vec<(u16, u8)> {
+    registry::SOLUTIONS
+        .iter()
+        .map(|entry| (entry.year, entry.day))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve_str() {
+        assert_eq!(
+            solve_str(2017, 1, Part::One, "91212129").unwrap(),
+            Answer::Int(9)
+        );
+        assert_eq!(
+            solve_str(
+                2018,
+                2,
+                Part::Two,
+                "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz"
+            )
+            .unwrap()
+            .to_string(),
+            "fgij"
+        );
+        assert!(solve_str(2017, 26, Part::One, "").is_err());
+        assert!(solve_str(2016, 1, Part::One, "R2, X3").is_err());
+    }
+
+    #[test]
+    fn test_available() {
+        let available = available();
+        assert!(available.contains(&(2015, 7)));
+        assert!(available.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+/// How detailed a diagnostic message is. `-v` shows debug messages and `-vv`
+/// trace messages as well; neither is shown by default.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Debug => write!(f, "DEBUG"),
+            Level::Trace => write!(f, "TRACE"),
+        }
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+static TARGETS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Enables messages up to `verbosity`, only from `targets` unless it is
+/// empty. Only the first call sets the targets.
+pub fn init(verbosity: u8, targets: Vec<String>) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+    let _ = TARGETS.set(targets);
+}
+
+/// The target of a message from `module`, its module path without the crate
+/// name, such as `year2015::day7`.
+pub fn target(module: &str) -> &str {
+    module.split_once("::").map_or("", |(_, rest)| rest)
+}
+
+/// Whether `target` is one of `targets` or nested in one of them.
+fn selected(targets: &[String], target: &str) -> bool {
+    targets.is_empty()
+        || targets.iter().any(|t| {
+            target
+                .strip_prefix(t.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        })
+}
+
+pub fn enabled(level: Level, module: &str) -> bool {
+    level as u8 <= VERBOSITY.load(Ordering::Relaxed)
+        && TARGETS
+            .get()
+            .is_none_or(|targets| selected(targets, target(module)))
+}
+
+#[doc(hidden)]
+pub fn write(level: Level, module: &str, args: fmt::Arguments) {
+    let _ = writeln!(
+        io::stderr().lock(),
+        "[{} {}] {}",
+        level,
+        target(module),
+        args
+    );
+}
+
+/// Logs a message at debug level, targeted at the calling module.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug, module_path!()) {
+            $crate::log::write($crate::log::Level::Debug, module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a message at trace level, targeted at the calling module.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace, module_path!()) {
+            $crate::log::write($crate::log::Level::Trace, module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        assert_eq!(target("aoc_rust::year2015::day7"), "year2015::day7");
+        assert_eq!(target("aoc_rust"), "");
+    }
+
+    #[test]
+    fn test_selected() {
+        let targets = vec!["year2015::day7".to_string(), "runner".to_string()];
+        assert!(selected(&targets, "year2015::day7"));
+        assert!(selected(&targets, "year2015::day7::test"));
+        assert!(selected(&targets, "runner"));
+        assert!(!selected(&targets, "year2015::day70"));
+        assert!(!selected(&targets, "year2018::day5"));
+        assert!(selected(&[], "year2018::day5"));
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        assert!(!enabled(Level::Debug, module_path!()));
+        assert!(!enabled(Level::Trace, "aoc_rust::year2018::day5"));
+    }
+}
@@ -1,4 +1,463 @@
-use aoc_rust::year2016;
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    process, thread,
+    time::Instant,
+};
+
+use anyhow::{anyhow, bail, Result};
+use aoc_rust::{
+    alloc::CountingAllocator,
+    answers::{self, AnswerStore, Verdict},
+    bench::{self, Baseline, Timing},
+    cli::{self, Command, USAGE},
+    compare,
+    explore::{Reply, Session},
+    input::{self, InputSource},
+    registry::{self, Entry},
+    report::{self, Format},
+    runner::{self, DayResult},
+    scaffold,
+    solution::Part,
+    status,
+    watch::{self, Answers, Watcher},
+};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn run_entry(
+    entry: &'static Entry,
+    source: &InputSource,
+    parts: &[Part],
+    format: Format,
+) -> Result<()> {
+    let outcomes = source
+        .load(entry.year, entry.day)
+        .and_then(|input| runner::run(entry, &input, parts));
+    match format {
+        Format::Text => runner::print_outcomes(&outcomes?),
+        format => report::print(&report::records(&[DayResult { entry, outcomes }]), format),
+    }
+    Ok(())
+}
+
+/// Returns whether every check passed (missing and unsolved parts do not fail).
+fn verify(year: Option<u16>, source: &InputSource, path: &Path, record: bool) -> Result<bool> {
+    let mut store = AnswerStore::load(path)?;
+    let (mut passed, mut failed, mut missing, mut unsolved) = (0, 0, 0, 0);
+
+    for entry in registry::by_year(year) {
+        for check in answers::verify(entry, source, &store) {
+            let label = format!("{} day {} part {}", check.year, check.day, check.part);
+            if check.verdict.is_failure() {
+                failed += 1;
+            }
+            match &check.verdict {
+                Verdict::Pass => {
+                    passed += 1;
+                    println!("{}: pass", label);
+                }
+                Verdict::Fail { expected, actual } => {
+                    println!(
+                        "{}: FAIL, expected {:?}, got {:?}",
+                        label,
+                        expected,
+                        actual.to_string()
+                    );
+                }
+                Verdict::Missing(answer) => {
+                    missing += 1;
+                    println!("{}: missing, got {:?}", label, answer.to_string());
+                    if record {
+                        store.insert(check.year, check.day, check.part, answer);
+                    }
+                }
+                Verdict::Unsolved => {
+                    unsolved += 1;
+                    println!("{}: unsolved", label);
+                }
+                Verdict::Error(e) => println!("{}: ERROR, {}", label, e),
+            }
+        }
+    }
+
+    if record && missing > 0 {
+        store.save(path)?;
+        println!("recorded {} new answers in {}", missing, path.display());
+    }
+    println!(
+        "{} passed, {} failed, {} missing, {} unsolved",
+        passed, failed, missing, unsolved
+    );
+
+    Ok(failed == 0)
+}
+
+struct BenchOptions {
+    runs: usize,
+    save: Option<PathBuf>,
+    compare: Option<PathBuf>,
+    threshold: u32,
+}
+
+/// Returns whether no stage regressed against the baseline.
+fn run_bench(entries: &[&Entry], source: &InputSource, options: &BenchOptions) -> Result<bool> {
+    if cfg!(debug_assertions) {
+        println!("note: this is a debug build, timings are not representative");
+    }
+    let baseline = options.compare.as_deref().map(Baseline::load).transpose()?;
+    let mut timings: Vec<Timing> = Vec::new();
+    let mut regressions = 0;
+
+    for entry in entries {
+        let input = source.load(entry.year, entry.day)?;
+        for timing in bench::bench(entry, &input, options.runs)? {
+            let stats = &timing.stats;
+            let mut line = format!(
+                "{} day {} {:<7} min {:>10}  median {:>10}  mean {:>10}",
+                timing.year,
+                timing.day,
+                timing.stage,
+                bench::format_duration(stats.min),
+                bench::format_duration(stats.median),
+                bench::format_duration(stats.mean)
+            );
+            let before = baseline
+                .as_ref()
+                .and_then(|b| b.get(timing.year, timing.day, timing.stage));
+            if let Some(before) = before {
+                line += &format!("  {:+.1}%", bench::change_percent(before, stats));
+                if bench::is_regression(before, stats, options.threshold) {
+                    regressions += 1;
+                    line += " REGRESSION";
+                }
+            }
+            println!("{}", line);
+            timings.push(timing);
+        }
+    }
+
+    if let Some(path) = &options.save {
+        timings.iter().collect::<Baseline>().save(path)?;
+        println!("saved baseline to {}", path.display());
+    }
+    if baseline.is_some() {
+        println!("{} regressions over {}%", regressions, options.threshold);
+    }
+
+    Ok(regressions == 0)
+}
+
+#[cfg(feature = "fetch")]
+fn fetch(year: u16, day: u8, input_dir: &Path, config: &Path) -> Result<()> {
+    use aoc_rust::client::{self, Client, Config};
+
+    let path = input::day_path(input_dir, year, day);
+    if client::is_cached(&path) {
+        println!("{} is already downloaded", path.display());
+        return Ok(());
+    }
+    let mut client =
+        Client::new(&Config::load(config)?).with_state_file(&client::state_file(config))?;
+    let (path, _) = client::fetch_input(&mut client, input_dir, year, day)?;
+    println!("wrote {}", path.display());
+    Ok(())
+}
+
+/// Returns whether the answer was accepted.
+#[cfg(feature = "fetch")]
+fn submit(
+    entry: &Entry,
+    part: Part,
+    source: &InputSource,
+    answers: &Path,
+    config: &Path,
+) -> Result<bool> {
+    use aoc_rust::client::{self, Client, Config, Submission};
+
+    let input = source.load(entry.year, entry.day)?;
+    let outcome = runner::run(entry, &input, &[part])?
+        .pop()
+        .expect("one outcome per part");
+    let answer = outcome.answer?;
+    let mut store = AnswerStore::load(answers)?;
+    let mut client =
+        Client::new(&Config::load(config)?).with_state_file(&client::state_file(config))?;
+
+    println!("submitting {}", answer);
+    let submission = client::submit(
+        &mut client,
+        &mut store,
+        entry.year,
+        entry.day,
+        part,
+        &answer,
+    )?;
+    println!("{}", submission);
+    if !matches!(submission, Submission::RateLimited(_)) {
+        store.save(answers)?;
+    }
+    Ok(submission == Submission::Correct)
+}
+
+#[cfg(not(feature = "fetch"))]
+fn submit(
+    _entry: &Entry,
+    _part: Part,
+    _source: &InputSource,
+    _answers: &Path,
+    _config: &Path,
+) -> Result<bool> {
+    bail!("submit needs the crate to be built with the fetch feature")
+}
+
+#[cfg(not(feature = "fetch"))]
+fn fetch(_year: u16, _day: u8, _input_dir: &Path, _config: &Path) -> Result<()> {
+    bail!("fetch needs the crate to be built with the fetch feature")
+}
+
+fn explore(entry: &'static Entry, source: &InputSource) -> Result<()> {
+    let mut session = Session::new(entry);
+    println!("{}", session.banner());
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        match session.handle(&line, source) {
+            Reply::Output(output) if output.is_empty() => {}
+            Reply::Output(output) => println!("{}", output),
+            Reply::Quit => return Ok(()),
+        }
+    }
+}
+
+fn watch(year: u16, day: u8, input_dir: &Path) -> Result<()> {
+    if !Path::new("Cargo.toml").exists() {
+        bail!("watch must be run from the root of the crate");
+    }
+    let source = Path::new("src")
+        .join(format!("year{}", year))
+        .join(format!("day{}.rs", day));
+    let input = input::day_path(input_dir, year, day);
+    println!(
+        "watching {} and {}, press Ctrl-C to stop",
+        source.display(),
+        input.display()
+    );
+    let built = watch::build_time();
+    let mut watcher = Watcher::new(vec![source.clone(), input]);
+    let mut previous = Answers::new();
+    let mut rebuild = true;
+
+    loop {
+        let answers = if rebuild {
+            match watch::run_tests(year, day) {
+                Ok((true, _)) => println!("tests passed"),
+                Ok((false, output)) => println!("tests FAILED\n{}", output.trim_end()),
+                Err(e) => println!("{}", cli::render_error(&e)),
+            }
+            watch::run_day(year, day, input_dir)
+        } else {
+            watch::run_in_process(year, day, input_dir)
+        };
+        match answers {
+            Ok(answers) => {
+                for line in watch::diff(&previous, &answers) {
+                    println!("{}", line);
+                }
+                previous = answers;
+            }
+            Err(e) => println!("{}", cli::render_error(&e)),
+        }
+
+        let changed = loop {
+            thread::sleep(watch::POLL_INTERVAL);
+            let changed = watcher.changed();
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        for path in &changed {
+            println!("\n{} changed", path.display());
+        }
+        // Only the input changed and this binary already has the current
+        // source, so rerun the day here instead of going through cargo.
+        rebuild = changed.contains(&source) || !watch::is_current(built, &source);
+    }
+}
+
 fn main() {
-    year2016::day10::solve();
+    if let Err(e) = try_main() {
+        eprintln!("{}", cli::render_error(&e));
+        process::exit(1);
+    }
+}
+
+fn try_main() -> Result<()> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let (verbosity, targets) = cli::take_log_options(&mut args)?;
+    aoc_rust::log::init(verbosity, targets);
+    if args.is_empty() {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    match Command::parse(&args)? {
+        Command::Run {
+            year,
+            day,
+            part,
+            input,
+            format,
+        } => {
+            let entry = registry::find(year, day)
+                .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?;
+            match part {
+                Some(part) => run_entry(entry, &input, &[part], format)?,
+                None => run_entry(entry, &input, &Part::ALL, format)?,
+            }
+        }
+        Command::List => {
+            for entry in registry::SOLUTIONS {
+                println!("{} day {}", entry.year, entry.day);
+            }
+        }
+        Command::All {
+            year,
+            input,
+            jobs,
+            format,
+        } => {
+            let jobs = match jobs {
+                0 => thread::available_parallelism().map_or(1, usize::from),
+                jobs => jobs,
+            };
+            let entries: Vec<&Entry> = registry::by_year(year).collect();
+            let start = Instant::now();
+            let results = runner::run_all(&entries, &input, jobs);
+            report::print(&report::records(&results), format);
+            if jobs > 1 && format == Format::Text {
+                println!(
+                    "wall clock: {} on {} threads",
+                    bench::format_duration(start.elapsed()),
+                    jobs
+                );
+            }
+        }
+        Command::Verify {
+            year,
+            input,
+            answers,
+            record,
+        } => {
+            if !verify(year, &input, &answers, record)? {
+                process::exit(1);
+            }
+        }
+        Command::Bench {
+            year,
+            day,
+            input,
+            runs,
+            save,
+            compare,
+            threshold,
+        } => {
+            let entries: Vec<&Entry> = match (year, day) {
+                (Some(year), Some(day)) => vec![registry::find(year, day)
+                    .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?],
+                _ => registry::by_year(year).collect(),
+            };
+            let options = BenchOptions {
+                runs,
+                save,
+                compare,
+                threshold,
+            };
+            if !run_bench(&entries, &input, &options)? {
+                process::exit(1);
+            }
+        }
+        Command::Explore { year, day, input } => {
+            let entry = registry::find(year, day)
+                .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?;
+            explore(entry, &input)?;
+        }
+        Command::Watch {
+            year,
+            day,
+            input_dir,
+        } => watch(year, day, &input_dir)?,
+        Command::Compare {
+            year,
+            day,
+            input_dir,
+            timeout,
+        } => {
+            let entry = registry::find(year, day)
+                .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?;
+            let paths = input::day_inputs(&input_dir, year, day)?;
+            let runs = compare::run(entry, &paths, timeout);
+            println!("{}", compare::render(&runs));
+            if runs.iter().any(|run| run.flagged()) {
+                process::exit(1);
+            }
+        }
+        Command::Status {
+            year,
+            input_dir,
+            answers,
+        } => {
+            let src = Path::new("src");
+            if !src.is_dir() {
+                println!("note: no src directory here, so modules and tests are not shown");
+            }
+            let years: Vec<u16> = status::years(src)
+                .into_iter()
+                .filter(|&y| year.is_none_or(|year| y == year))
+                .collect();
+            let store = AnswerStore::load(&answers)?;
+            let statuses = status::collect(&years, src, &input_dir, &store);
+            println!("{}", status::render(&statuses));
+        }
+        Command::New {
+            year,
+            day,
+            input_dir,
+        } => {
+            if !Path::new("Cargo.toml").exists() {
+                bail!("new must be run from the root of the crate");
+            }
+            for path in scaffold::scaffold(Path::new("src"), &input_dir, year, day)? {
+                println!("wrote {}", path.display());
+            }
+        }
+        Command::Fetch {
+            year,
+            day,
+            input_dir,
+            config,
+        } => fetch(year, day, &input_dir, &config)?,
+        Command::Submit {
+            year,
+            day,
+            part,
+            input,
+            answers,
+            config,
+        } => {
+            let entry = registry::find(year, day)
+                .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?;
+            if !submit(entry, part, &input, &answers, &config)? {
+                process::exit(1);
+            }
+        }
+    }
+
+    Ok(())
 }
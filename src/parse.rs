@@ -0,0 +1,172 @@
+use std::{error, fmt, str::FromStr};
+
+/// A parse failure pointing at the offending part of the puzzle input.
+///
+/// `line` is only known once the error has passed through something that
+/// iterates over lines, such as [`parse_lines`]; a `FromStr` impl only sees
+/// its own line and records it as `context` for rendering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+    pub context: String,
+}
+
+impl ParseError {
+    /// Creates an error for `text` within `context`.
+    ///
+    /// When `text` is a slice of `context` the column is taken from its
+    /// position, otherwise the error points at the start of the line.
+    pub fn new(context: &str, text: &str, expected: impl Into<String>) -> Self {
+        let start = context.as_ptr() as usize;
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&offset| offset + text.len() <= context.len())
+            .unwrap_or(0);
+        ParseError {
+            line: None,
+            column: context[..offset].chars().count() + 1,
+            text: text.to_string(),
+            expected: expected.into(),
+            context: context.to_string(),
+        }
+    }
+
+    /// Creates an error for the end of `context`, where more was expected.
+    pub fn end(context: &str, expected: impl Into<String>) -> Self {
+        Self::new(context, &context[context.len()..], expected)
+    }
+
+    /// Re-anchors an error from parsing `part`, a slice of `context`, so that
+    /// it points into the whole of `context`.
+    pub fn within(self, context: &str, part: &str) -> Self {
+        let offset = ParseError::new(context, part, "").column - 1;
+        ParseError {
+            column: self.column + offset,
+            context: context.to_string(),
+            ..self
+        }
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    /// Renders the error followed by its line with the offending text marked.
+    pub fn render(&self) -> String {
+        let gutter = self.line.map_or(String::new(), |line| line.to_string());
+        let padding = " ".repeat(gutter.len());
+        let marker = "^".repeat(self.text.chars().count().max(1));
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            padding,
+            gutter,
+            self.context,
+            padding,
+            " ".repeat(self.column - 1),
+            marker
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+        write!(f, "column {}: expected {}, ", self.column, self.expected)?;
+        if self.text.is_empty() {
+            write!(f, "found end of line")
+        } else {
+            write!(f, "found {:?}", self.text)
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parses `token`, a slice of `context`, as a number.
+pub fn number<T: FromStr>(context: &str, token: &str) -> Result<T, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::new(context, token, "a number"))
+}
+
+/// Parses each line of `input`, numbering the lines of any error.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| line.parse().map_err(|e: ParseError| e.at_line(n + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_column() {
+        let line = "#1 @ 1,x: 4x4";
+        let error = ParseError::new(line, &line[7..8], "a number");
+        assert_eq!(error.column, 8);
+        assert_eq!(error.text, "x");
+
+        let error = ParseError::new(line, "elsewhere", "a claim");
+        assert_eq!(error.column, 1);
+
+        let error = ParseError::end(line, "a size");
+        assert_eq!(error.column, 14);
+        assert_eq!(error.text, "");
+
+        let claim = &line[5..];
+        let error = ParseError::new(claim, &claim[2..3], "a number").within(line, claim);
+        assert_eq!(error.column, 8);
+        assert_eq!(error.context, line);
+    }
+
+    #[test]
+    fn test_display() {
+        let line = "R5, X2";
+        let error = ParseError::new(line, &line[4..5], "L or R").at_line(3);
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 5: expected L or R, found \"X\""
+        );
+        assert_eq!(
+            error.render(),
+            "line 3, column 5: expected L or R, found \"X\"\n  |\n3 | R5, X2\n  |     ^"
+        );
+        assert_eq!(
+            ParseError::end("R", "a number").to_string(),
+            "column 2: expected a number, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        struct Num(u8);
+
+        impl FromStr for Num {
+            type Err = ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                number(s, s).map(Num)
+            }
+        }
+
+        let nums: Vec<Num> = parse_lines("1\n2\n3").unwrap();
+        assert_eq!(nums.iter().map(|n| n.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let error = parse_lines::<Num>("1\n2\nthree").err().unwrap();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.text, "three");
+    }
+}
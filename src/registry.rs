@@ -0,0 +1,122 @@
+use crate::solution::DynSolution;
+#[cfg(feature = "year2015")]
+use crate::year2015;
+#[cfg(feature = "year2016")]
+use crate::year2016;
+#[cfg(feature = "year2017")]
+use crate::year2017;
+#[cfg(feature = "year2018")]
+use crate::year2018;
+
+pub struct Entry {
+    pub year: u16,
+    pub day: u8,
+    pub solution: &'static dyn DynSolution,
+}
+
+impl Entry {
+    pub const fn new(year: u16, day: u8, solution: &'static dyn DynSolution) -> Self {
+        Entry {
+            year,
+            day,
+            solution,
+        }
+    }
+}
+
+pub static SOLUTIONS: &[Entry] = &[
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 1, &year2015::day1::Day1),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 2, &year2015::day2::Day2),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 3, &year2015::day3::Day3),
+    #[cfg(all(feature = "year2015", feature = "hashing"))]
+    Entry::new(2015, 4, &year2015::day4::Day4),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 5, &year2015::day5::Day5),
+    #[cfg(all(feature = "year2015", feature = "regex"))]
+    Entry::new(2015, 6, &year2015::day6::Day6),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 7, &year2015::day7::Day7),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 8, &year2015::day8::Day8),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 9, &year2015::day9::Day9),
+    #[cfg(feature = "year2015")]
+    Entry::new(2015, 10, &year2015::day10::Day10),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 1, &year2016::day1::Day1),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 2, &year2016::day2::Day2),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 3, &year2016::day3::Day3),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 4, &year2016::day4::Day4),
+    #[cfg(all(feature = "year2016", feature = "hashing"))]
+    Entry::new(2016, 5, &year2016::day5::Day5),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 6, &year2016::day6::Day6),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 7, &year2016::day7::Day7),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 8, &year2016::day8::Day8),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 9, &year2016::day9::Day9),
+    #[cfg(feature = "year2016")]
+    Entry::new(2016, 10, &year2016::day10::Day10),
+    #[cfg(feature = "year2017")]
+    Entry::new(2017, 1, &year2017::day1::Day1),
+    #[cfg(feature = "year2017")]
+    Entry::new(2017, 2, &year2017::day2::Day2),
+    #[cfg(feature = "year2017")]
+    Entry::new(2017, 3, &year2017::day3::Day3),
+    #[cfg(feature = "year2017")]
+    Entry::new(2017, 4, &year2017::day4::Day4),
+    #[cfg(feature = "year2017")]
+    Entry::new(2017, 5, &year2017::day5::Day5),
+    #[cfg(feature = "year2018")]
+    Entry::new(2018, 1, &year2018::day1::Day1),
+    #[cfg(feature = "year2018")]
+    Entry::new(2018, 2, &year2018::day2::Day2),
+    #[cfg(feature = "year2018")]
+    Entry::new(2018, 3, &year2018::day3::Day3),
+    #[cfg(all(feature = "year2018", feature = "regex"))]
+    Entry::new(2018, 4, &year2018::day4::Day4),
+    #[cfg(feature = "year2018")]
+    Entry::new(2018, 5, &year2018::day5::Day5),
+];
+
+pub fn find(year: u16, day: u8) -> Option<&'static Entry> {
+    SOLUTIONS.iter().find(|e| e.year == year && e.day == day)
+}
+
+pub fn by_year(year: Option<u16>) -> impl Iterator<Item = &'static Entry> {
+    SOLUTIONS
+        .iter()
+        .filter(move |e| year.is_none_or(|y| e.year == y))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted_and_unique() {
+        assert!(SOLUTIONS
+            .windows(2)
+            .all(|w| (w[0].year, w[0].day) < (w[1].year, w[1].day)));
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(2016, 8).map(|e| (e.year, e.day)), Some((2016, 8)));
+        assert!(find(2016, 25).is_none());
+    }
+
+    #[test]
+    fn test_by_year() {
+        assert!(by_year(Some(2017)).all(|e| e.year == 2017));
+        assert_eq!(by_year(None).count(), SOLUTIONS.len());
+    }
+}
@@ -0,0 +1,453 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::{bail, Error};
+
+use crate::{
+    alloc::{format_bytes, Usage},
+    bench::format_duration,
+    runner::DayResult,
+    solution::{Answer, Part, Unsolved},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            other => bail!("unknown format {}, expected json, csv or text", other),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Status {
+    Ok,
+    Unsolved,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Unsolved => write!(f, "unsolved"),
+            Status::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// One row of machine-readable output. Days that fail before any part runs,
+/// for example because their input is missing, get a single row without a
+/// part.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<Part>,
+    pub status: Status,
+    pub answer: Option<Answer>,
+    pub error: Option<String>,
+    pub elapsed: Option<Duration>,
+    pub memory: Option<Usage>,
+}
+
+pub fn records(results: &[DayResult]) -> Vec<Record> {
+    let mut records = Vec::new();
+    for result in results {
+        let (year, day) = (result.entry.year, result.entry.day);
+        let outcomes = match &result.outcomes {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                records.push(Record {
+                    year,
+                    day,
+                    part: None,
+                    status: Status::Error,
+                    answer: None,
+                    error: Some(format!("{:#}", e)),
+                    elapsed: None,
+                    memory: None,
+                });
+                continue;
+            }
+        };
+        for outcome in outcomes {
+            let (status, answer, error, elapsed, memory) = match &outcome.answer {
+                Ok(answer) => (
+                    Status::Ok,
+                    Some(answer.clone()),
+                    None,
+                    Some(outcome.elapsed),
+                    outcome.memory,
+                ),
+                Err(e) if e.is::<Unsolved>() => (Status::Unsolved, None, None, None, None),
+                Err(e) => (
+                    Status::Error,
+                    None,
+                    Some(format!("{:#}", e)),
+                    Some(outcome.elapsed),
+                    outcome.memory,
+                ),
+            };
+            records.push(Record {
+                year,
+                day,
+                part: Some(outcome.part),
+                status,
+                answer,
+                error,
+                elapsed,
+                memory,
+            });
+        }
+    }
+    records
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Renders the records as a JSON array with one object per line.
+///
+/// Integer answers become JSON numbers and all other answers strings. The
+/// memory fields are null unless the counting allocator is installed.
+pub fn to_json(records: &[Record]) -> String {
+    let null = || "null".to_string();
+    let objects: Vec<String> = records
+        .iter()
+        .map(|r| {
+            format!(
+                "  {{\"year\": {}, \"day\": {}, \"part\": {}, \"answer\": {}, \"elapsed_ns\": {}, \"peak_bytes\": {}, \"allocations\": {}, \"status\": {}, \"error\": {}}}",
+                r.year,
+                r.day,
+                r.part.map_or_else(null, |p| p.to_string()),
+                match &r.answer {
+                    Some(Answer::Int(n)) => n.to_string(),
+                    Some(answer) => json_string(&answer.to_string()),
+                    None => null(),
+                },
+                r.elapsed.map_or_else(null, |d| d.as_nanos().to_string()),
+                r.memory.map_or_else(null, |m| m.peak.to_string()),
+                r.memory.map_or_else(null, |m| m.allocations.to_string()),
+                json_string(&r.status.to_string()),
+                r.error.as_deref().map_or_else(null, json_string),
+            )
+        })
+        .collect();
+    if objects.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Renders the records as CSV with a header row. Missing values are left
+/// empty.
+pub fn to_csv(records: &[Record]) -> String {
+    let mut out =
+        String::from("year,day,part,answer,elapsed_ns,peak_bytes,allocations,status,error\n");
+    for r in records {
+        let fields = [
+            r.year.to_string(),
+            r.day.to_string(),
+            r.part.map(|p| p.to_string()).unwrap_or_default(),
+            r.answer.as_ref().map(|a| a.to_string()).unwrap_or_default(),
+            r.elapsed
+                .map(|d| d.as_nanos().to_string())
+                .unwrap_or_default(),
+            r.memory.map(|m| m.peak.to_string()).unwrap_or_default(),
+            r.memory
+                .map(|m| m.allocations.to_string())
+                .unwrap_or_default(),
+            r.status.to_string(),
+            r.error.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Splits CSV as written by [`to_csv`] into rows of fields, including the
+/// header row.
+pub fn parse_csv(s: &str) -> Result<Vec<Vec<String>>, Error> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut chars = s.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        bail!("unterminated quoted field");
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Builds the rows of the summary table, along with the total time spent in
+/// solved parts. Grid answers are listed separately below the table.
+fn table_rows(records: &[Record]) -> (Vec<[String; 7]>, Vec<String>, Duration) {
+    let mut rows = Vec::new();
+    let mut grids = Vec::new();
+    let mut total = Duration::ZERO;
+
+    for r in records {
+        let part = r.part.map_or_else(|| "-".to_string(), |p| p.to_string());
+        let answer = match (&r.answer, &r.error) {
+            (Some(Answer::Grid(grid)), _) => {
+                grids.push(format!("{} day {} part {}:\n{}", r.year, r.day, part, grid));
+                "(grid, see below)".to_string()
+            }
+            (Some(answer), _) => answer.to_string(),
+            (None, Some(error)) => format!("error: {}", error),
+            (None, None) => r.status.to_string(),
+        };
+        total += r.elapsed.unwrap_or_default();
+        rows.push([
+            r.year.to_string(),
+            r.day.to_string(),
+            part,
+            answer,
+            r.elapsed.map_or_else(|| "-".to_string(), format_duration),
+            r.memory
+                .map_or_else(|| "-".to_string(), |m| format_bytes(m.peak)),
+            r.memory
+                .map_or_else(|| "-".to_string(), |m| m.allocations.to_string()),
+        ]);
+    }
+
+    (rows, grids, total)
+}
+
+/// Prints a year/day/part/answer/time/memory table followed by the total
+/// time.
+pub fn print_table(records: &[Record]) {
+    let header = ["year", "day", "part", "answer", "time", "peak", "allocs"].map(String::from);
+    let (rows, grids, total) = table_rows(records);
+
+    let mut widths = header.clone().map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let print_row = |row: &[String; 7]| {
+        println!(
+            "{:<w0$}  {:>w1$}  {:>w2$}  {:<w3$}  {:>w4$}  {:>w5$}  {:>w6$}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            row[5],
+            row[6],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w4 = widths[4],
+            w5 = widths[5],
+            w6 = widths[6],
+        )
+    };
+
+    print_row(&header);
+    println!("{}", "-".repeat(widths.iter().sum::<usize>() + 2 * 6));
+    for row in &rows {
+        print_row(row);
+    }
+    println!("total: {}", format_duration(total));
+
+    for grid in grids {
+        println!("\n{}", grid);
+    }
+}
+
+pub fn print(records: &[Record], format: Format) {
+    match format {
+        Format::Text => print_table(records),
+        Format::Json => print!("{}", to_json(records)),
+        Format::Csv => print!("{}", to_csv(records)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{input::InputSource, registry, runner};
+
+    fn sample() -> Vec<Record> {
+        vec![
+            Record {
+                year: 2016,
+                day: 8,
+                part: Some(Part::Two),
+                status: Status::Ok,
+                answer: Some(Answer::Grid("#.\n.#".to_string())),
+                error: None,
+                elapsed: Some(Duration::from_nanos(1500)),
+                memory: Some(Usage {
+                    peak: 2048,
+                    allocations: 3,
+                }),
+            },
+            Record {
+                year: 2018,
+                day: 1,
+                part: Some(Part::One),
+                status: Status::Ok,
+                answer: Some(Answer::Int(430)),
+                error: None,
+                elapsed: Some(Duration::from_nanos(20)),
+                memory: None,
+            },
+            Record {
+                year: 2018,
+                day: 2,
+                part: None,
+                status: Status::Error,
+                answer: None,
+                error: Some("could not read \"Day2.txt\", no such file".to_string()),
+                elapsed: None,
+                memory: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_records() {
+        let entries: Vec<_> = [(2018, 1), (2017, 5), (2016, 10)]
+            .iter()
+            .map(|&(year, day)| registry::find(year, day).unwrap())
+            .collect();
+        let source = InputSource::Literal("0\n3\n0\n1\n-3".to_string());
+        let records = records(&runner::run_all(&entries, &source, 4));
+
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| {
+                (
+                    r.year,
+                    r.day,
+                    r.status,
+                    r.answer.as_ref().map(|a| a.to_string()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2016, 10, Status::Unsolved, None),
+                (2016, 10, Status::Unsolved, None),
+                (2017, 5, Status::Ok, Some("5".to_string())),
+                (2017, 5, Status::Ok, Some("10".to_string())),
+                (2018, 1, Status::Ok, Some("1".to_string())),
+                (2018, 1, Status::Ok, Some("0".to_string())),
+            ]
+        );
+
+        let (rows, grids, _) = table_rows(&records);
+        assert_eq!(rows[0][3], "unsolved");
+        assert_eq!(rows[0][4], "-");
+        assert_eq!(rows[0][5], "-");
+        assert_ne!(rows[2][5], "-");
+        assert!(grids.is_empty());
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            to_json(&sample()),
+            r##"[
+  {"year": 2016, "day": 8, "part": 2, "answer": "#.\n.#", "elapsed_ns": 1500, "peak_bytes": 2048, "allocations": 3, "status": "ok", "error": null},
+  {"year": 2018, "day": 1, "part": 1, "answer": 430, "elapsed_ns": 20, "peak_bytes": null, "allocations": null, "status": "ok", "error": null},
+  {"year": 2018, "day": 2, "part": null, "answer": null, "elapsed_ns": null, "peak_bytes": null, "allocations": null, "status": "error", "error": "could not read \"Day2.txt\", no such file"}
+]
+"##
+        );
+        assert_eq!(to_json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            to_csv(&sample()),
+            "year,day,part,answer,elapsed_ns,peak_bytes,allocations,status,error\n\
+             2016,8,2,\"#.\n.#\",1500,2048,3,ok,\n\
+             2018,1,1,430,20,,,ok,\n\
+             2018,2,,,,,,error,\"could not read \"\"Day2.txt\"\", no such file\"\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv(&to_csv(&sample())).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0][3], "answer");
+        assert_eq!(rows[1][3], "#.\n.#");
+        assert_eq!(rows[3][8], "could not read \"Day2.txt\", no such file");
+        assert!(rows.iter().all(|row| row.len() == 9));
+        assert!(parse_csv("a,\"b").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    any::Any,
+    error, fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    alloc::{self, Usage},
+    debug,
+    input::InputSource,
+    registry::Entry,
+    solution::{Answer, Part},
+};
+
+pub struct Outcome {
+    pub part: Part,
+    pub answer: Result<Answer>,
+    pub elapsed: Duration,
+    /// Heap used by the part, if the counting allocator is installed.
+    pub memory: Option<Usage>,
+}
+
+/// Parses `input` once and runs the requested parts against it.
+pub fn run(entry: &Entry, input: &str, parts: &[Part]) -> Result<Vec<Outcome>> {
+    let start = Instant::now();
+    let parsed = entry
+        .solution
+        .parse_dyn(input)
+        .with_context(|| format!("failed to parse input for {} day {}", entry.year, entry.day))?;
+    debug!(
+        "parsed {} day {} in {:?}",
+        entry.year,
+        entry.day,
+        start.elapsed()
+    );
+
+    Ok(parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let (answer, memory) = alloc::measure(|| entry.solution.part_dyn(&*parsed, part));
+            Outcome {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+                memory,
+            }
+        })
+        .collect())
+}
+
+pub fn print_outcomes(outcomes: &[Outcome]) {
+    for outcome in outcomes {
+        let memory = outcome.memory.map_or(String::new(), |usage| {
+            format!(
+                " (peak {}, {} allocations)",
+                alloc::format_bytes(usage.peak),
+                usage.allocations
+            )
+        });
+        match &outcome.answer {
+            Ok(Answer::Grid(grid)) => println!("part {}{}:\n{}", outcome.part, memory, grid),
+            Ok(answer) => println!("part {}: {}{}", outcome.part, answer, memory),
+            Err(e) => println!("part {}: error: {:#}{}", outcome.part, e, memory),
+        }
+    }
+}
+
+pub struct DayResult {
+    pub entry: &'static Entry,
+    pub outcomes: Result<Vec<Outcome>>,
+}
+
+/// Runs both parts of every entry on `jobs` worker threads.
+///
+/// Workers pull days from a shared queue, so a slow day only occupies one
+/// worker. A day that panics is reported as a [`Failure::Panicked`] error
+/// instead of taking the other days down with it. Results are sorted by year
+/// and day.
+pub fn run_all(entries: &[&'static Entry], source: &InputSource, jobs: usize) -> Vec<DayResult> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(entries.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, entries.len().max(1)) {
+            scope.spawn(|| {
+                while let Some(&entry) = entries.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let outcomes = source
+                        .load(entry.year, entry.day)
+                        .and_then(|input| catch_panic(|| run(entry, &input, &Part::ALL)));
+                    results.lock().unwrap().push(DayResult { entry, outcomes });
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|r| (r.entry.year, r.entry.day));
+    results
+}
+
+/// Why [`run_guarded`] got no answer out of a solver.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Failure {
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Panicked(message) => write!(f, "panicked: {}", message),
+            Failure::TimedOut(timeout) => write!(f, "timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl error::Error for Failure {}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "unknown panic".to_string(), |m| m.to_string()),
+    }
+}
+
+/// Runs `f`, turning a panic into a [`Failure::Panicked`] error.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(Failure::Panicked(panic_message(payload)).into()))
+}
+
+/// Like [`run`] on both parts, but on a thread of its own, turning panics
+/// into [`Failure::Panicked`] errors and giving up with
+/// [`Failure::TimedOut`] on the parts not done within `timeout`.
+///
+/// A solver that times out cannot be stopped, so its thread keeps running
+/// in the background until it finishes or the process exits.
+pub fn run_guarded(
+    entry: &'static Entry,
+    input: String,
+    timeout: Duration,
+) -> Result<Vec<Outcome>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name(format!("{} day {}", entry.year, entry.day))
+        .spawn(move || {
+            let parsed =
+                match panic::catch_unwind(AssertUnwindSafe(|| entry.solution.parse_dyn(&input))) {
+                    Ok(Ok(parsed)) => parsed,
+                    Ok(Err(e)) => {
+                        let _ = sender.send(Err(e.context(format!(
+                            "failed to parse input for {} day {}",
+                            entry.year, entry.day
+                        ))));
+                        return;
+                    }
+                    Err(payload) => {
+                        let _ = sender.send(Err(Failure::Panicked(panic_message(payload)).into()));
+                        return;
+                    }
+                };
+            for part in Part::ALL {
+                let start = Instant::now();
+                let (answer, memory) =
+                    alloc::measure(|| catch_panic(|| entry.solution.part_dyn(&*parsed, part)));
+                let outcome = Outcome {
+                    part,
+                    answer,
+                    elapsed: start.elapsed(),
+                    memory,
+                };
+                if sender.send(Ok(outcome)).is_err() {
+                    return;
+                }
+            }
+        })
+        .context("could not start a solver thread")?;
+
+    let deadline = Instant::now() + timeout;
+    let mut outcomes = Vec::new();
+    for part in Part::ALL {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(outcome) => outcomes.push(outcome?),
+            Err(RecvTimeoutError::Timeout) => outcomes.push(Outcome {
+                part,
+                answer: Err(Failure::TimedOut(timeout).into()),
+                elapsed: timeout,
+                memory: None,
+            }),
+            Err(RecvTimeoutError::Disconnected) => {
+                bail!("the solver thread stopped without answering")
+            }
+        }
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{registry, solution::Solution};
+
+    #[test]
+    fn test_run_all() {
+        let entries: Vec<_> = [(2018, 1), (2017, 5), (2016, 10)]
+            .iter()
+            .map(|&(year, day)| registry::find(year, day).unwrap())
+            .collect();
+        let source = InputSource::Literal("0\n3\n0\n1\n-3".to_string());
+        let results = run_all(&entries, &source, 4);
+
+        let days: Vec<_> = results
+            .iter()
+            .map(|r| (r.entry.year, r.entry.day))
+            .collect();
+        assert_eq!(days, vec![(2016, 10), (2017, 5), (2018, 1)]);
+        let answers: Vec<_> = results[1]
+            .outcomes
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|o| o.answer.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(answers, vec!["5", "10"]);
+    }
+
+    struct Misbehaving;
+
+    impl Solution for Misbehaving {
+        type Input = u64;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            match input {
+                "panic" => panic!("bad input"),
+                _ => Ok(input.parse()?),
+            }
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            match input {
+                0 => panic!("division by {}", input),
+                _ => Ok((100 / input).into()),
+            }
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            thread::sleep(Duration::from_millis(*input));
+            Ok((*input).into())
+        }
+    }
+
+    static MISBEHAVING: Entry = Entry::new(2000, 1, &Misbehaving);
+
+    #[test]
+    fn test_run_guarded() {
+        let timeout = Duration::from_millis(500);
+        let answers = |input: &str| -> Result<Vec<String>> {
+            Ok(run_guarded(&MISBEHAVING, input.to_string(), timeout)?
+                .into_iter()
+                .map(|o| o.answer.map_or_else(|e| e.to_string(), |a| a.to_string()))
+                .collect())
+        };
+
+        assert_eq!(answers("5").unwrap(), vec!["20", "5"]);
+        assert_eq!(answers("0").unwrap(), vec!["panicked: division by 0", "0"]);
+        assert_eq!(answers("2000").unwrap(), vec!["0", "timed out after 500ms"]);
+        let error = answers("panic").unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<Failure>(),
+            Some(&Failure::Panicked("bad input".to_string()))
+        );
+        assert!(answers("five").is_err());
+    }
+
+    #[test]
+    fn test_run_all_panics() {
+        let entries = [&MISBEHAVING, registry::find(2017, 5).unwrap()];
+        let source = InputSource::Literal("0".to_string());
+        let results = run_all(&entries, &source, 2);
+
+        let error = results[0].outcomes.as_ref().err();
+        assert_eq!(
+            error.and_then(|e| e.downcast_ref::<Failure>()),
+            Some(&Failure::Panicked("division by 0".to_string()))
+        );
+        assert!(results[1].outcomes.is_ok());
+    }
+}
@@ -0,0 +1,431 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::input;
+
+fn template(day: u8) -> String {
+    format!(
+        r#"use anyhow::Result;
+
+use crate::solution::{{Answer, Solution, Unsolved}};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {{
+        Ok(input.lines().map(String::from).collect())
+    }}
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {{
+        Err(Unsolved.into())
+    }}
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {{
+        Err(Unsolved.into())
+    }}
+}}
+
+#[cfg(test)]
+mod test {{
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    fn test_parse() {{
+        assert!(Day{day}::parse(EXAMPLE).is_ok());
+    }}
+}}
+"#
+    )
+}
+
+/// Adds `pub mod <name>;` to a list of module declarations, uncommenting it
+/// if it is there but disabled and otherwise keeping the list sorted. A new
+/// declaration is preceded by `attribute` if one is given.
+fn declare_module(source: &str, name: &str, attribute: Option<&str>) -> Result<String> {
+    let declaration = format!("pub mod {};", name);
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+
+    let module_name = |line: &str| {
+        line.trim_start_matches("//")
+            .trim()
+            .strip_prefix("pub mod ")
+            .and_then(|rest| rest.strip_suffix(';'))
+            .map(String::from)
+    };
+
+    if lines.iter().any(|line| line.trim() == declaration) {
+        bail!("module {} is already declared", name);
+    }
+    if let Some(line) = lines
+        .iter_mut()
+        .find(|line| module_name(line).as_deref() == Some(name))
+    {
+        *line = declaration;
+    } else {
+        let modules: Vec<_> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| module_name(line).map(|m| (i, m)))
+            .collect();
+        let at = modules
+            .iter()
+            .find(|(_, m)| m.as_str() > name)
+            .map(|&(i, _)| attributes_start(&lines, i))
+            .or_else(|| modules.last().map(|&(i, _)| i + 1))
+            .unwrap_or(lines.len());
+        lines.insert(at, declaration);
+        if let Some(attribute) = attribute {
+            lines.insert(at, attribute.to_string());
+        }
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The index of the first of the attribute lines directly above `lines[i]`.
+fn attributes_start(lines: &[String], mut i: usize) -> usize {
+    while i > 0 && lines[i - 1].trim_start().starts_with("#[") {
+        i -= 1;
+    }
+    i
+}
+
+fn feature_gate(year: u16) -> String {
+    format!("#[cfg(feature = \"year{}\")]", year)
+}
+
+fn entry_key(line: &str) -> Option<(u16, u8)> {
+    let rest = line.trim().strip_prefix("Entry::new(")?;
+    let mut fields = rest.split(',').map(str::trim);
+    Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+}
+
+/// Adds an entry for the new day to the registry, importing its year module
+/// if this is the first day of that year.
+fn register(source: &str, year: u16, day: u8) -> Result<String> {
+    let module = format!("year{}", year);
+    let import = format!("use crate::{};", module);
+    let entry = format!(
+        "    Entry::new({}, {}, &{}::day{}::Day{}),",
+        year, day, module, day, day
+    );
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+
+    if lines
+        .iter()
+        .any(|line| entry_key(line) == Some((year, day)))
+    {
+        bail!("{} day {} is already registered", year, day);
+    }
+
+    if !lines.contains(&import) {
+        let imports: Vec<usize> = (0..lines.len())
+            .filter(|&i| lines[i].starts_with("use crate::"))
+            .collect();
+        let last = *imports
+            .last()
+            .context("could not find the module imports in the registry")?;
+        let at = imports
+            .iter()
+            .find(|&&i| lines[i].starts_with("use crate::year") && lines[i] > import)
+            .map(|&i| attributes_start(&lines, i))
+            .unwrap_or(last + 1);
+        lines.insert(at, import);
+        lines.insert(at, feature_gate(year));
+    }
+
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub static SOLUTIONS"))
+        .context("could not find SOLUTIONS in the registry")?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line == "];")
+            .context("could not find the end of SOLUTIONS")?;
+    let at = (start + 1..end)
+        .find(|&i| entry_key(&lines[i]).is_some_and(|key| key > (year, day)))
+        .map(|i| attributes_start(&lines, i))
+        .unwrap_or(end);
+    lines.insert(at, entry);
+    lines.insert(at, format!("    {}", feature_gate(year)));
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds a `year<year>` feature to the manifest and enables it by default.
+fn add_feature(source: &str, year: u16) -> Result<String> {
+    let feature = format!("year{}", year);
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+
+    let default = lines
+        .iter_mut()
+        .find(|line| line.starts_with("default = ["))
+        .context("could not find the default features in the manifest")?;
+    let items = default["default = [".len()..]
+        .trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty());
+    let mut items: Vec<String> = items.map(String::from).collect();
+    let quoted = format!("\"{}\"", feature);
+    if items.contains(&quoted) {
+        bail!("feature {} already exists", feature);
+    }
+    let at = items
+        .iter()
+        .position(|item| !item.starts_with("\"year") || *item > quoted)
+        .unwrap_or(items.len());
+    items.insert(at, quoted);
+    *default = format!("default = [{}]", items.join(", "));
+
+    let years: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].starts_with("year") && lines[i].ends_with("= []"))
+        .collect();
+    let at = years
+        .iter()
+        .find(|&&i| lines[i].as_str() > feature.as_str())
+        .copied()
+        .or_else(|| years.last().map(|&i| i + 1))
+        .context("could not find the year features in the manifest")?;
+    lines.insert(at, format!("{} = []", feature));
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn update(path: &Path, edit: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+    let source =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let updated = edit(&source).with_context(|| format!("could not update {}", path.display()))?;
+    fs::write(path, updated).with_context(|| format!("could not write {}", path.display()))
+}
+
+/// Creates the module of a new day under `src_dir`, declares and registers
+/// it, and creates its empty input file under `input_dir`. The first day of a
+/// year also gets a feature for that year in the manifest next to `src_dir`.
+///
+/// Returns the paths that were created or modified.
+pub fn scaffold(src_dir: &Path, input_dir: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>> {
+    let year_dir = src_dir.join(format!("year{}", year));
+    let module = year_dir.join(format!("day{}.rs", day));
+    // Some days exist as empty placeholder files, which may be overwritten.
+    if fs::metadata(&module).is_ok_and(|m| m.len() > 0) {
+        bail!("{} already exists", module.display());
+    }
+
+    let registry = src_dir.join("registry.rs");
+    let registered = fs::read_to_string(&registry)
+        .with_context(|| format!("could not read {}", registry.display()))?;
+    let registered = register(&registered, year, day)?;
+
+    let mut touched = Vec::new();
+    let year_mod = year_dir.join("mod.rs");
+    if !year_mod.exists() {
+        fs::create_dir_all(&year_dir)
+            .with_context(|| format!("could not create {}", year_dir.display()))?;
+        fs::write(&year_mod, "")
+            .with_context(|| format!("could not write {}", year_mod.display()))?;
+        let lib = src_dir.join("lib.rs");
+        update(&lib, |s| {
+            declare_module(s, &format!("year{}", year), Some(&feature_gate(year)))
+        })?;
+        touched.push(lib);
+        let manifest = src_dir
+            .parent()
+            .context("the source directory has no parent")?
+            .join("Cargo.toml");
+        update(&manifest, |s| add_feature(s, year))?;
+        touched.push(manifest);
+    }
+    update(&year_mod, |s| {
+        declare_module(s, &format!("day{}", day), None)
+    })?;
+    touched.push(year_mod);
+
+    fs::write(&module, template(day))
+        .with_context(|| format!("could not write {}", module.display()))?;
+    touched.push(module);
+
+    fs::write(&registry, registered)
+        .with_context(|| format!("could not write {}", registry.display()))?;
+    touched.push(registry);
+
+    let input = input::day_path(input_dir, year, day);
+    if !input.exists() {
+        if let Some(parent) = input.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("could not create {}", parent.display()))?;
+        }
+        fs::write(&input, "").with_context(|| format!("could not write {}", input.display()))?;
+        touched.push(input);
+    }
+
+    Ok(touched)
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::*;
+
+    const REGISTRY: &str = "use crate::solution::DynSolution;
+#[cfg(feature = \"year2015\")]
+use crate::year2015;
+#[cfg(feature = \"year2017\")]
+use crate::year2017;
+
+pub static SOLUTIONS: &[Entry] = &[
+    #[cfg(feature = \"year2015\")]
+    Entry::new(2015, 1, &year2015::day1::Day1),
+    #[cfg(all(feature = \"year2015\", feature = \"hashing\"))]
+    Entry::new(2015, 4, &year2015::day4::Day4),
+    #[cfg(feature = \"year2017\")]
+    Entry::new(2017, 1, &year2017::day1::Day1),
+];
+";
+
+    const MANIFEST: &str = "[features]
+default = [\"year2015\", \"year2017\", \"hashing\"]
+year2015 = []
+year2017 = []
+hashing = [\"dep:md5\"]
+";
+
+    #[test]
+    fn test_declare_module() {
+        let source = "pub mod day1;\npub mod day3;\n// pub mod day6;\n";
+        assert_eq!(
+            declare_module(source, "day6", None).unwrap(),
+            "pub mod day1;\npub mod day3;\npub mod day6;\n"
+        );
+        assert_eq!(
+            declare_module(source, "day2", None).unwrap(),
+            "pub mod day1;\npub mod day2;\npub mod day3;\n// pub mod day6;\n"
+        );
+        assert_eq!(
+            declare_module(source, "day9", None).unwrap(),
+            "pub mod day1;\npub mod day3;\n// pub mod day6;\npub mod day9;\n"
+        );
+        assert_eq!(declare_module("", "day1", None).unwrap(), "pub mod day1;\n");
+        assert!(declare_module(source, "day3", None).is_err());
+
+        let source = "pub mod day1;\n#[cfg(feature = \"hashing\")]\npub mod day4;\n";
+        assert_eq!(
+            declare_module(source, "day2", Some("#[cfg(test)]")).unwrap(),
+            "pub mod day1;\n#[cfg(test)]\npub mod day2;\n#[cfg(feature = \"hashing\")]\npub mod day4;\n"
+        );
+    }
+
+    #[test]
+    fn test_register() {
+        let updated = register(REGISTRY, 2015, 3).unwrap();
+        assert!(updated.contains(
+            "    Entry::new(2015, 1, &year2015::day1::Day1),\n    \
+             #[cfg(feature = \"year2015\")]\n    \
+             Entry::new(2015, 3, &year2015::day3::Day3),\n    \
+             #[cfg(all(feature = \"year2015\", feature = \"hashing\"))]\n"
+        ));
+
+        let updated = register(REGISTRY, 2016, 1).unwrap();
+        assert!(updated.contains(
+            "use crate::year2015;\n\
+             #[cfg(feature = \"year2016\")]\n\
+             use crate::year2016;\n\
+             #[cfg(feature = \"year2017\")]\n"
+        ));
+        assert!(updated.contains(
+            "    #[cfg(feature = \"year2016\")]\n    \
+             Entry::new(2016, 1, &year2016::day1::Day1),\n    \
+             #[cfg(feature = \"year2017\")]\n    \
+             Entry::new(2017, 1, &year2017::day1::Day1),\n"
+        ));
+
+        let updated = register(REGISTRY, 2018, 1).unwrap();
+        assert!(updated.contains(
+            "use crate::year2017;\n#[cfg(feature = \"year2018\")]\nuse crate::year2018;\n\n"
+        ));
+        assert!(updated.contains("    Entry::new(2018, 1, &year2018::day1::Day1),\n];"));
+
+        assert!(register(REGISTRY, 2015, 4).is_err());
+    }
+
+    #[test]
+    fn test_add_feature() {
+        assert_eq!(
+            add_feature(MANIFEST, 2016).unwrap(),
+            "[features]
+default = [\"year2015\", \"year2016\", \"year2017\", \"hashing\"]
+year2015 = []
+year2016 = []
+year2017 = []
+hashing = [\"dep:md5\"]
+"
+        );
+        assert!(add_feature(MANIFEST, 2019)
+            .unwrap()
+            .contains("\"year2017\", \"year2019\", \"hashing\"]\nyear2015 = []\nyear2017 = []\nyear2019 = []\n"));
+        assert!(add_feature(MANIFEST, 2015).is_err());
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let dir = env::temp_dir().join(format!("aoc_rust_scaffold_{}", std::process::id()));
+        let (src, inputs) = (dir.join("src"), dir.join("inputs"));
+        fs::create_dir_all(src.join("year2017")).unwrap();
+        fs::write(dir.join("Cargo.toml"), MANIFEST).unwrap();
+        fs::write(
+            src.join("lib.rs"),
+            "pub mod registry;\n#[cfg(feature = \"year2015\")]\npub mod year2015;\n#[cfg(feature = \"year2017\")]\npub mod year2017;\n",
+        )
+        .unwrap();
+        fs::write(src.join("registry.rs"), REGISTRY).unwrap();
+        fs::write(
+            src.join("year2017/mod.rs"),
+            "pub mod day1;\n// pub mod day6;\n",
+        )
+        .unwrap();
+        fs::write(src.join("year2017/day6.rs"), "").unwrap();
+
+        let touched = scaffold(&src, &inputs, 2017, 6).unwrap();
+        assert_eq!(touched.len(), 4);
+        assert_eq!(
+            fs::read_to_string(src.join("year2017/mod.rs")).unwrap(),
+            "pub mod day1;\npub mod day6;\n"
+        );
+        assert!(fs::read_to_string(src.join("year2017/day6.rs"))
+            .unwrap()
+            .contains("impl Solution for Day6"));
+        assert_eq!(
+            fs::read_to_string(inputs.join("Year2017/Day6.txt")).unwrap(),
+            ""
+        );
+        assert!(scaffold(&src, &inputs, 2017, 6).is_err());
+
+        let touched = scaffold(&src, &inputs, 2016, 1).unwrap();
+        assert_eq!(touched.len(), 6);
+        assert_eq!(
+            fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "pub mod registry;\n#[cfg(feature = \"year2015\")]\npub mod year2015;\n\
+             #[cfg(feature = \"year2016\")]\npub mod year2016;\n\
+             #[cfg(feature = \"year2017\")]\npub mod year2017;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(src.join("year2016/mod.rs")).unwrap(),
+            "pub mod day1;\n"
+        );
+        assert!(fs::read_to_string(dir.join("Cargo.toml"))
+            .unwrap()
+            .contains("year2016 = []"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+use std::{any::Any, error, fmt};
+
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// The result of a single puzzle part.
+///
+/// `Grid` holds multi-line answers such as the letters drawn on the
+/// year2016 day8 screen, which are meant to be read by a human.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    Grid(String),
+}
+
+impl Answer {
+    pub fn from_pixels<R: AsRef<[bool]>>(rows: &[R]) -> Self {
+        let lines: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                row.as_ref()
+                    .iter()
+                    .map(|&lit| if lit { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        Answer::Grid(lines.join("\n"))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(s) | Answer::Grid(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(i64::from(n))
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i16, i32, i64, u8, u16, u32);
+
+/// Like `answer_from_int!`, for types that can hold values beyond `i64`. Such
+/// a value would otherwise wrap into a wrong but plausible answer that
+/// `verify` or `submit` could accept, so it panics instead.
+macro_rules! answer_from_wide_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    let n = i64::try_from(n)
+                        .unwrap_or_else(|_| panic!("answer {} does not fit in an i64", n));
+                    Answer::Int(n)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_wide_int!(u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// Returned by parts that have not been solved yet.
+#[derive(Debug)]
+pub struct Unsolved;
+
+impl fmt::Display for Unsolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not solved yet")
+    }
+}
+
+impl error::Error for Unsolved {}
+
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {
+        Err(Unsolved.into())
+    }
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        Err(Unsolved.into())
+    }
+}
+
+/// Object-safe view of a [`Solution`], so days with different input types
+/// can live side by side in the registry.
+pub trait DynSolution: Sync {
+    fn parse_dyn(&self, input: &str) -> Result<Box<dyn Any>>;
+
+    fn part_dyn(&self, input: &dyn Any, part: Part) -> Result<Answer>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse_dyn(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn part_dyn(&self, input: &dyn Any, part: Part) -> Result<Answer> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .ok_or_else(|| anyhow!("input was parsed by a different solution"))?;
+        match part {
+            Part::One => S::part1(input),
+            Part::Two => S::part2(input),
+        }
+    }
+}
+
+/// Parses `input` with `S` and checks each expected answer, where `part` is
+/// the name used in [`examples!`].
+#[cfg(all(
+    test,
+    any(
+        feature = "year2015",
+        feature = "year2016",
+        feature = "year2017",
+        feature = "year2018"
+    )
+))]
+pub(crate) fn check_example<S: Solution>(name: &str, input: &str, expected: &[(&str, Answer)]) {
+    let parsed =
+        S::parse(input).unwrap_or_else(|e| panic!("example {} failed to parse: {:#}", name, e));
+    for (part, answer) in expected {
+        let actual = match *part {
+            "part1" => S::part1(&parsed),
+            "part2" => S::part2(&parsed),
+            other => panic!("example {} names unknown part {}", name, other),
+        }
+        .unwrap_or_else(|e| panic!("example {} {} failed: {:#}", name, part, e));
+        assert_eq!(
+            actual.to_string(),
+            answer.to_string(),
+            "example {} {}",
+            name,
+            part
+        );
+    }
+}
+
+/// Declares the puzzle examples of a day and generates one test per example:
+///
+/// ```ignore
+/// examples!(Day5 {
+///     maze: "0\n3\n0\n1\n-3" => part1: 5, part2: 10;
+/// });
+/// ```
+///
+/// Either part may be left out when an example only covers the other one.
+#[cfg(all(
+    test,
+    any(
+        feature = "year2015",
+        feature = "year2016",
+        feature = "year2017",
+        feature = "year2018"
+    )
+))]
+macro_rules! examples {
+    ($day:ty { $($name:ident: $input:expr => $($part:ident: $expected:expr),+;)+ }) => {
+        $(
+            #[test]
+            fn $name() {
+                $crate::solution::check_example::<$day>(
+                    stringify!($name),
+                    $input,
+                    &[$((stringify!($part), $crate::solution::Answer::from($expected))),+],
+                );
+            }
+        )+
+    };
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "year2015",
+        feature = "year2016",
+        feature = "year2017",
+        feature = "year2018"
+    )
+))]
+pub(crate) use examples;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input
+                .split_whitespace()
+                .map(|n| n.parse())
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            Ok(input.iter().sum::<i64>().into())
+        }
+    }
+
+    #[test]
+    fn test_dyn_solution() {
+        let solution: &dyn DynSolution = &Sum;
+        let input = solution.parse_dyn("1 2 3").unwrap();
+        assert_eq!(
+            solution.part_dyn(&*input, Part::One).unwrap(),
+            Answer::Int(6)
+        );
+        assert!(solution
+            .part_dyn(&*input, Part::Two)
+            .unwrap_err()
+            .is::<Unsolved>());
+    }
+
+    #[test]
+    fn test_answer_from_pixels() {
+        assert_eq!(
+            Answer::from_pixels(&[[true, false], [false, true]]),
+            Answer::Grid("#.\n.#".to_string())
+        );
+    }
+
+    #[test]
+    fn test_answer_from_int() {
+        assert_eq!(Answer::from(u32::MAX), Answer::Int(4294967295));
+        assert_eq!(Answer::from(i64::MAX as u64), Answer::Int(i64::MAX));
+        assert_eq!(Answer::from(7usize), Answer::Int(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in an i64")]
+    fn test_answer_from_wide_int() {
+        let _ = Answer::from(u64::MAX);
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, read_to_string},
+    path::Path,
+};
+
+use crate::{answers::AnswerStore, input, registry, solution::Part};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DayStatus {
+    pub year: u16,
+    pub day: u8,
+    pub registered: bool,
+    /// Whether the day has a non-empty module, registered or not.
+    pub source: bool,
+    /// Parts with an answer in the answers store.
+    pub solved: usize,
+    pub tests: bool,
+    pub example_tests: bool,
+    pub input: bool,
+}
+
+impl DayStatus {
+    fn stars(&self) -> &'static str {
+        match (self.solved, self.registered, self.source) {
+            (2, _, _) => "**",
+            (1, _, _) => "* ",
+            (_, true, _) => "..",
+            (_, false, true) => "~~",
+            _ => "  ",
+        }
+    }
+
+    fn flags(&self) -> String {
+        let flag = |set, c| if set { c } else { '-' };
+        [
+            flag(self.tests, 't'),
+            flag(self.example_tests, 'e'),
+            flag(self.input, 'i'),
+        ]
+        .iter()
+        .collect()
+    }
+}
+
+/// Years that have registered days or a module directory under `src_dir`.
+pub fn years(src_dir: &Path) -> Vec<u16> {
+    let mut years: BTreeSet<u16> = registry::SOLUTIONS.iter().map(|e| e.year).collect();
+    if let Ok(dirs) = fs::read_dir(src_dir) {
+        years.extend(dirs.flatten().filter_map(|dir| {
+            dir.file_name()
+                .to_str()?
+                .strip_prefix("year")?
+                .parse::<u16>()
+                .ok()
+        }));
+    }
+    years.into_iter().collect()
+}
+
+/// Gathers the status of every day of `years` from the registry, the day
+/// modules under `src_dir`, the inputs under `input_dir` and `store`.
+pub fn collect(
+    years: &[u16],
+    src_dir: &Path,
+    input_dir: &Path,
+    store: &AnswerStore,
+) -> Vec<DayStatus> {
+    let mut statuses = Vec::new();
+    for &year in years {
+        for day in 1..=25 {
+            let module = src_dir
+                .join(format!("year{}", year))
+                .join(format!("day{}.rs", day));
+            let source = read_to_string(module).unwrap_or_default();
+            let input = input::day_path(input_dir, year, day);
+            let example_tests = source.contains("examples!(");
+            statuses.push(DayStatus {
+                year,
+                day,
+                registered: registry::find(year, day).is_some(),
+                source: !source.trim().is_empty(),
+                solved: Part::ALL
+                    .iter()
+                    .filter(|&&part| store.get(year, day, part).is_some())
+                    .count(),
+                // The tests generated by examples! count as tests too.
+                tests: example_tests || source.contains("#[test]"),
+                example_tests,
+                input: fs::metadata(input).is_ok_and(|m| m.len() > 0),
+            });
+        }
+    }
+    statuses
+}
+
+pub const LEGEND: &str = "** both parts   * one part   .. registered, no answers   \
+                          ~~ unregistered module\n\
+                          t tests   e example tests   i input file";
+
+/// Renders one calendar of five rows of five days per year.
+pub fn render(statuses: &[DayStatus]) -> String {
+    let mut out = String::new();
+    for year in statuses.chunk_by(|a, b| a.year == b.year) {
+        let stars: usize = year.iter().map(|s| s.solved).sum();
+        let registered = year.iter().filter(|s| s.registered).count();
+        out += &format!(
+            "{}  {} stars, {} days registered\n",
+            year[0].year, stars, registered
+        );
+        for week in year.chunks(5) {
+            let cells: Vec<String> = week
+                .iter()
+                .map(|s| format!("{:>2} {} {}", s.day, s.stars(), s.flags()))
+                .collect();
+            out += &format!("  {}\n", cells.join("   "));
+        }
+        out.push('\n');
+    }
+    out + LEGEND
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let dir = env::temp_dir().join(format!("aoc_rust_status_{}", std::process::id()));
+        let (src, inputs) = (dir.join("src"), dir.join("inputs"));
+        fs::create_dir_all(src.join("year2016")).unwrap();
+        fs::create_dir_all(src.join("year2030")).unwrap();
+        fs::write(
+            src.join("year2016/day1.rs"),
+            "#[test]\nfn test_example() { examples!(Day1 { }); }",
+        )
+        .unwrap();
+        fs::write(src.join("year2016/day2.rs"), "#[test]\nfn test_parse() {}").unwrap();
+        fs::write(src.join("year2016/day11.rs"), "pub struct Day11;").unwrap();
+        fs::write(src.join("year2016/day12.rs"), "").unwrap();
+        fs::create_dir_all(inputs.join("Year2016")).unwrap();
+        fs::write(inputs.join("Year2016/Day1.txt"), "R2, L3").unwrap();
+        fs::write(inputs.join("Year2016/Day2.txt"), "").unwrap();
+        let store = AnswerStore::parse("2016 1 1 5\n2016 1 2 4\n2016 2 1 1985").unwrap();
+
+        assert!(years(&src).contains(&2030));
+        let statuses = collect(&[2016], &src, &inputs, &store);
+        assert_eq!(statuses.len(), 25);
+        assert_eq!(
+            statuses[0],
+            DayStatus {
+                year: 2016,
+                day: 1,
+                registered: true,
+                source: true,
+                solved: 2,
+                tests: true,
+                example_tests: true,
+                input: true,
+            }
+        );
+        assert_eq!(
+            (statuses[1].stars(), statuses[1].flags().as_str()),
+            ("* ", "t--")
+        );
+        assert_eq!(statuses[10].stars(), "~~");
+        assert_eq!(statuses[11].stars(), "  ");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let statuses: Vec<DayStatus> = (1..=25)
+            .map(|day| DayStatus {
+                year: 2017,
+                day,
+                registered: day <= 6,
+                source: day <= 7,
+                solved: match day {
+                    1..=4 => 2,
+                    5 => 1,
+                    _ => 0,
+                },
+                tests: day <= 3,
+                example_tests: day == 1,
+                input: day <= 6,
+            })
+            .collect();
+        let rendered = render(&statuses);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "2017  9 stars, 6 days registered");
+        assert_eq!(
+            lines[1],
+            "   1 ** tei    2 ** t-i    3 ** t-i    4 ** --i    5 *  --i"
+        );
+        assert_eq!(
+            lines[2],
+            "   6 .. --i    7 ~~ ---    8    ---    9    ---   10    ---"
+        );
+        assert_eq!(lines.len(), 9);
+    }
+}
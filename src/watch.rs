@@ -0,0 +1,303 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    compare::DEFAULT_TIMEOUT_SECS,
+    input, registry, report,
+    runner::{self, Outcome},
+    solution::{Part, Unsolved},
+};
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Polls files for changes to their modification time or size.
+pub struct Watcher {
+    files: Vec<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Watcher {
+            files: paths
+                .into_iter()
+                .map(|path| {
+                    let stamp = stamp(&path);
+                    (path, stamp)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the files that changed since the watcher was created or last
+    /// asked.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, previous) in &mut self.files {
+            let current = stamp(path);
+            if current != *previous {
+                *previous = current;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+pub type Answers = BTreeMap<Part, String>;
+
+/// Reads the answers from the output of `run --format csv`, failing if the
+/// day could not be run at all.
+pub fn answers_from_csv(csv: &str) -> Result<Answers> {
+    let rows = report::parse_csv(csv)?;
+    let (header, rows) = rows.split_first().context("no header in the output")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| anyhow!("no {} column in the output", name))
+    };
+    let (part, answer, status, error) = (
+        column("part")?,
+        column("answer")?,
+        column("status")?,
+        column("error")?,
+    );
+
+    let mut answers = Answers::new();
+    for row in rows {
+        let field = |i: usize| row.get(i).map_or("", String::as_str);
+        let part = match field(part) {
+            "1" => Part::One,
+            "2" => Part::Two,
+            _ => bail!("{}", field(error)),
+        };
+        let value = match field(status) {
+            "ok" => field(answer).to_string(),
+            "error" => format!("error: {}", field(error)),
+            other => other.to_string(),
+        };
+        answers.insert(part, value);
+    }
+    Ok(answers)
+}
+
+/// Reads the answers from the outcomes of a run, labelled like the output of
+/// `run --format csv`.
+pub fn answers_from_outcomes(outcomes: &[Outcome]) -> Answers {
+    outcomes
+        .iter()
+        .map(|outcome| {
+            let value = match &outcome.answer {
+                Ok(answer) => answer.to_string(),
+                Err(e) if e.is::<Unsolved>() => "unsolved".to_string(),
+                Err(e) => format!("error: {:#}", e),
+            };
+            (outcome.part, value)
+        })
+        .collect()
+}
+
+/// Describes how the answers changed since the previous run.
+pub fn diff(previous: &Answers, current: &Answers) -> Vec<String> {
+    let mut lines = Vec::new();
+    for part in Part::ALL {
+        let line = match (previous.get(&part), current.get(&part)) {
+            (Some(before), Some(after)) if before == after => {
+                format!("part {}: {} (unchanged)", part, after)
+            }
+            (Some(before), Some(after)) if before.contains('\n') || after.contains('\n') => {
+                format!("part {} changed from\n{}\nto\n{}", part, before, after)
+            }
+            (Some(before), Some(after)) => format!("part {}: {} -> {}", part, before, after),
+            (None, Some(after)) => format!("part {}: {}", part, after),
+            (Some(before), None) => format!("part {}: no longer reported, was {}", part, before),
+            (None, None) => continue,
+        };
+        lines.push(line);
+    }
+    lines
+}
+
+/// A cargo command for the toolchain and profile this binary was built with.
+fn cargo(subcommand: &str) -> Command {
+    let mut command = Command::new(env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo")));
+    command.arg(subcommand).arg("--quiet");
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    command
+}
+
+/// Runs the tests of a day, including its examples, returning whether they
+/// passed along with their output.
+pub fn run_tests(year: u16, day: u8) -> Result<(bool, String)> {
+    let output = cargo("test")
+        .args(["--lib", &format!("year{}::day{}::", year, day)])
+        .output()
+        .context("could not run cargo test")?;
+    let text = String::from_utf8_lossy(&output.stdout).into_owned()
+        + &String::from_utf8_lossy(&output.stderr);
+    Ok((output.status.success(), text))
+}
+
+/// When the running binary was built, read once at startup since `cargo run`
+/// may replace the file while it runs.
+pub fn build_time() -> Option<SystemTime> {
+    fs::metadata(env::current_exe().ok()?).ok()?.modified().ok()
+}
+
+/// Whether a binary built at `built` already contains the current `source`,
+/// so that the day can be rerun in this process without a rebuild.
+pub fn is_current(built: Option<SystemTime>, source: &Path) -> bool {
+    match (built, stamp(source)) {
+        (Some(built), Some((modified, _))) => modified <= built,
+        _ => false,
+    }
+}
+
+/// Runs both parts of a day in this process, catching panics and hangs like
+/// `compare` does. Only use this when [`is_current`] holds.
+pub fn run_in_process(year: u16, day: u8, input_dir: &Path) -> Result<Answers> {
+    let entry = registry::find(year, day)
+        .ok_or_else(|| anyhow!("{} day {} is not in the registry", year, day))?;
+    let path = input::day_path(input_dir, year, day);
+    let input =
+        fs::read_to_string(&path).with_context(|| format!("could not read {}", path.display()))?;
+    let timeout = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+    Ok(answers_from_outcomes(&runner::run_guarded(
+        entry, input, timeout,
+    )?))
+}
+
+/// Rebuilds the binary if needed and runs both parts of a day in it.
+pub fn run_day(year: u16, day: u8, input_dir: &Path) -> Result<Answers> {
+    let output = cargo("run")
+        .arg("--")
+        .args(["run", &year.to_string(), &day.to_string()])
+        .arg("--input-dir")
+        .arg(input_dir)
+        .args(["--format", "csv"])
+        .output()
+        .context("could not run cargo run")?;
+    if !output.status.success() {
+        bail!(
+            "cargo run failed\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    answers_from_csv(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, thread};
+
+    use super::*;
+
+    #[test]
+    fn test_watcher() {
+        let dir = env::temp_dir().join(format!("aoc_rust_watch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (source, input) = (dir.join("day1.rs"), dir.join("Day1.txt"));
+        fs::write(&source, "pub struct Day1;").unwrap();
+
+        let mut watcher = Watcher::new(vec![source.clone(), input.clone()]);
+        assert!(watcher.changed().is_empty());
+        fs::write(&input, "R2, L3").unwrap();
+        assert_eq!(watcher.changed(), vec![input.clone()]);
+        assert!(watcher.changed().is_empty());
+
+        // Make sure the modification time moves even on coarse filesystems.
+        thread::sleep(Duration::from_millis(20));
+        fs::write(&source, "pub struct Day1; // edited").unwrap();
+        assert_eq!(watcher.changed(), vec![source]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_answers_from_csv() {
+        let csv = "year,day,part,answer,elapsed_ns,peak_bytes,allocations,status,error\n\
+                   2016,8,1,110,1500,,,ok,\n\
+                   2016,8,2,\"#.\n.#\",1500,,,ok,\n";
+        let answers = answers_from_csv(csv).unwrap();
+        assert_eq!(answers[&Part::One], "110");
+        assert_eq!(answers[&Part::Two], "#.\n.#");
+
+        let csv = "year,day,part,answer,elapsed_ns,peak_bytes,allocations,status,error\n\
+                   2016,8,,,,,,error,could not parse\n";
+        assert!(answers_from_csv(csv).is_err());
+    }
+
+    #[test]
+    fn test_is_current() {
+        let dir = env::temp_dir().join(format!("aoc_rust_current_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("day1.rs");
+        fs::write(&source, "pub struct Day1;").unwrap();
+        let modified = stamp(&source).unwrap().0;
+
+        assert!(is_current(Some(modified + Duration::from_secs(1)), &source));
+        assert!(!is_current(
+            Some(modified - Duration::from_secs(1)),
+            &source
+        ));
+        assert!(!is_current(None, &source));
+        assert!(!is_current(Some(modified), &dir.join("missing.rs")));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_in_process() {
+        let dir = env::temp_dir().join(format!("aoc_rust_inproc_{}", std::process::id()));
+        let path = input::day_path(&dir, 2017, 1);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "1122").unwrap();
+
+        let answers = run_in_process(2017, 1, &dir).unwrap();
+        assert_eq!(answers[&Part::One], "3");
+        assert_eq!(answers[&Part::Two], "0");
+        fs::write(&path, "12x4").unwrap();
+        assert!(run_in_process(2017, 1, &dir).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = Answers::from([(Part::One, "110".to_string())]);
+        let after = Answers::from([
+            (Part::One, "110".to_string()),
+            (Part::Two, "ZJHRKCPLYJ".to_string()),
+        ]);
+        assert_eq!(
+            diff(&before, &after),
+            vec!["part 1: 110 (unchanged)", "part 2: ZJHRKCPLYJ"]
+        );
+        let changed = Answers::from([(Part::One, "112".to_string())]);
+        assert_eq!(
+            diff(&after, &changed),
+            vec![
+                "part 1: 110 -> 112",
+                "part 2: no longer reported, was ZJHRKCPLYJ"
+            ]
+        );
+    }
+}
@@ -1,30 +1,79 @@
-use std::{error, fs::read_to_string};
+use anyhow::Result;
 
-fn parse_char(acc: i64, c: char) -> i64 {
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Solution},
+};
+
+fn parse_char(line: &str, i: usize, c: char) -> Result<i64, ParseError> {
     match c {
-        '(' => acc + 1,
-        ')' => acc - 1,
-        _ => panic!("wrong input char"),
+        '(' => Ok(1),
+        ')' => Ok(-1),
+        _ => Err(ParseError::new(line, &line[i..i + c.len_utf8()], "( or )")),
+    }
+}
+
+fn parse_steps(input: &str) -> Result<Vec<i64>, ParseError> {
+    let mut steps = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        for (i, c) in line.char_indices() {
+            steps.push(parse_char(line, i, c).map_err(|e| e.at_line(n + 1))?);
+        }
     }
+    Ok(steps)
 }
 
-fn find_basement(input: String) -> usize {
+fn find_basement(steps: &[i64]) -> usize {
     let mut current_level = 0;
-    for c in input.char_indices() {
-        current_level = parse_char(current_level, c.1);
+    for (i, step) in steps.iter().enumerate() {
+        current_level += step;
         if current_level < 0 {
-            return c.0 + 1;
+            return i + 1;
         };
     }
 
     0
 }
 
-fn parse_inst(input: String) -> i64 {
-    input.chars().fold(0, parse_char)
+fn parse_inst(steps: &[i64]) -> i64 {
+    steps.iter().sum()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_steps(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(parse_inst(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(find_basement(input).into())
+    }
 }
 
-pub fn solve() {
-    let file_content = read_to_string("inputs/Year2015/Day1.txt").unwrap();
-    println!("{}", find_basement(file_content));
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    examples!(Day1 {
+        balanced: "(())" => part1: 0;
+        up: "(((" => part1: 3;
+        down: ")())())" => part1: -3;
+        first_char_basement: ")" => part2: 1;
+        late_basement: "()())" => part2: 5;
+        trailing_newline: "(()\n" => part1: 1;
+    });
+
+    #[test]
+    fn test_parse_steps() {
+        let error = parse_steps("(()\n)x").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), 2));
+    }
 }
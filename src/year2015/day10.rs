@@ -1,3 +1,7 @@
+use anyhow::Result;
+
+use crate::solution::{Answer, Solution};
+
 fn look_and_say(s: String) -> String {
     let mut result = String::new();
     let mut chars = s.chars().peekable();
@@ -17,10 +21,28 @@ fn look_and_say(s: String) -> String {
     result
 }
 
-pub fn solve() {
-    let mut content = "1113122113".to_string();
-    for _ in 0..50 {
+fn look_and_say_length(s: &str, rounds: usize) -> usize {
+    let mut content = s.to_string();
+    for _ in 0..rounds {
         content = look_and_say(content);
     }
-    println!("{}", content.len());
+    content.len()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(look_and_say_length(input, 40).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(look_and_say_length(input, 50).into())
+    }
 }
@@ -1,23 +1,34 @@
-use std::{fs::read_to_string, num::ParseIntError, str::FromStr};
+use std::str::FromStr;
+
+use anyhow::Result;
+
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
-struct Present {
+pub struct Present {
     length: i32,
     width: i32,
     height: i32,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct ParsePresentError;
-
 impl FromStr for Present {
-    type Err = ParsePresentError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split('x').collect();
-        let length = parts[0].parse::<i32>().map_err(|_| ParsePresentError)?;
-        let width = parts[1].parse::<i32>().map_err(|_| ParsePresentError)?;
-        let height = parts[2].parse::<i32>().map_err(|_| ParsePresentError)?;
+        let mut parts = s.split('x');
+        let mut side = || match parts.next() {
+            Some(part) => parse::number(s, part),
+            None => Err(ParseError::end(s, "dimensions like 2x3x4")),
+        };
+        let length = side()?;
+        let width = side()?;
+        let height = side()?;
+        if let Some(rest) = parts.next() {
+            return Err(ParseError::new(s, rest, "only three dimensions"));
+        }
 
         Ok(Present {
             length,
@@ -29,7 +40,7 @@ impl FromStr for Present {
 
 impl Present {
     fn get_total_area(&self) -> i32 {
-        2 * (self.width * self.length + self.height * self.length + self.width + self.height)
+        2 * (self.width * self.length + self.height * self.length + self.width * self.height)
     }
 
     fn get_smallest_area(&self) -> i32 {
@@ -37,7 +48,7 @@ impl Present {
 
         sides.sort_unstable();
 
-        sides.into_iter().take(2).fold(1, |acc, side| acc * side)
+        sides.into_iter().take(2).product()
     }
 
     fn get_volume(&self) -> i32 {
@@ -56,29 +67,38 @@ impl Present {
     }
 }
 
-fn parse_presents(input: String) -> Vec<Present> {
-    input
-        .lines()
-        .map(|l| Present::from_str(l).unwrap())
-        .collect()
+fn parse_presents(input: &str) -> Result<Vec<Present>, ParseError> {
+    parse::parse_lines(input)
 }
 
-fn calculate_area(presents: Vec<Present>) -> i32 {
-    presents.into_iter().fold(0, |acc, present| {
+fn calculate_area(presents: &[Present]) -> i32 {
+    presents.iter().fold(0, |acc, present| {
         acc + present.get_total_area() + present.get_smallest_area()
     })
 }
 
-fn calculate_ribbon(presents: Vec<Present>) -> i32 {
-    presents.into_iter().fold(0, |acc, present| {
+fn calculate_ribbon(presents: &[Present]) -> i32 {
+    presents.iter().fold(0, |acc, present| {
         acc + present.get_smallest_perimeter() + present.get_volume()
     })
 }
 
-pub fn solve() {
-    let file_content = read_to_string("inputs/Year2015/Day2.txt").unwrap();
-    let presents = parse_presents(file_content);
-    println!("{:?}", calculate_ribbon(presents))
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Present>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_presents(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(calculate_area(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(calculate_ribbon(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -94,29 +114,35 @@ mod tests {
                 width: 11,
                 height: 24
             }
-        )
+        );
+
+        let error = Present::from_str("3x1yx4").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "1y"));
+        assert_eq!(Present::from_str("3x11").unwrap_err().column, 5);
+        assert_eq!(Present::from_str("3x1x2x4").unwrap_err().text, "4");
     }
 
     #[test]
     fn test_parse_multiple_presents() {
-        let mut pres_vec = Vec::new();
-        pres_vec.push(Present {
-            length: 3,
-            width: 11,
-            height: 24,
-        });
-        pres_vec.push(Present {
-            length: 13,
-            width: 5,
-            height: 19,
-        });
-        pres_vec.push(Present {
-            length: 1,
-            width: 9,
-            height: 27,
-        });
+        let pres_vec = vec![
+            Present {
+                length: 3,
+                width: 11,
+                height: 24,
+            },
+            Present {
+                length: 13,
+                width: 5,
+                height: 19,
+            },
+            Present {
+                length: 1,
+                width: 9,
+                height: 27,
+            },
+        ];
         assert_eq!(
-            parse_presents("3x11x24\n13x5x19\n1x9x27".to_string()),
+            parse_presents("3x11x24\n13x5x19\n1x9x27").unwrap(),
             pres_vec
         );
     }
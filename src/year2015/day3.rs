@@ -1,147 +1,156 @@
-use std::{collections::HashMap, fs::read_to_string};
+use anyhow::Result;
 
-#[derive(PartialEq, Eq, Debug, Hash, Copy, Clone)]
-struct Cell {
-    x: i32,
-    y: i32,
-}
-
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
+use crate::{
+    geometry::{self, Direction, Point},
+    grid::SparseGrid,
+    parse::ParseError,
+    solution::{Answer, Solution},
+    trace,
+};
 
 #[derive(Debug)]
 struct Visited {
-    cells: HashMap<Cell, i32>,
-    current_cell: Cell,
+    cells: SparseGrid<usize>,
+    current_cell: Point<i32>,
 }
 
 impl Visited {
     fn new() -> Self {
-        let mut cells = HashMap::new();
-        cells.insert(Cell { x: 0, y: 0 }, 1);
-
         Visited {
-            cells,
-            current_cell: Cell { x: 0, y: 0 },
+            cells: [Point::ORIGIN].into_iter().collect(),
+            current_cell: Point::ORIGIN,
         }
     }
 
     fn visit_cell(&mut self, dir: Direction) {
-        self.current_cell = match dir {
-            Direction::Left => Cell {
-                x: self.current_cell.x - 1,
-                y: self.current_cell.y,
-            },
-            Direction::Right => Cell {
-                x: self.current_cell.x + 1,
-                y: self.current_cell.y,
-            },
-            Direction::Up => Cell {
-                x: self.current_cell.x,
-                y: self.current_cell.y + 1,
-            },
-            Direction::Down => Cell {
-                x: self.current_cell.x,
-                y: self.current_cell.y - 1,
-            },
-        };
-
-        self.cells
-            .entry(self.current_cell)
-            .and_modify(|e| *e += 1)
-            .or_insert(1);
+        self.current_cell = self.current_cell.step(dir);
+        self.cells.visit(self.current_cell);
     }
 
-    fn parse_move(&mut self, inst: char) {
-        match inst {
-            '<' => self.visit_cell(Direction::Left),
-            '>' => self.visit_cell(Direction::Right),
-            '^' => self.visit_cell(Direction::Up),
-            'v' => self.visit_cell(Direction::Down),
-            _ => panic!("wrong instruction"),
-        }
+    /// Draws the houses with how many presents each got, `+` for ten or more.
+    fn render(&self) -> String {
+        self.cells.render(|_, visits| match visits {
+            Some(&n) if n < 10 => char::from_digit(n as u32, 10).unwrap(),
+            Some(_) => '+',
+            None => ' ',
+        })
     }
 }
 
-pub fn solve() {
-    let file_content = read_to_string("inputs/Year2015/Day3.txt").unwrap();
+fn parse_moves(input: &str) -> Result<Vec<Direction>, ParseError> {
+    let mut moves = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        moves.extend(
+            geometry::parse_chars(line, "one of <>^v", Direction::from_arrow)
+                .map_err(|e| e.at_line(n + 1))?,
+        );
+    }
+    Ok(moves)
+}
+
+fn count_houses(moves: &[Direction]) -> usize {
+    let mut visited = Visited::new();
+    for &dir in moves {
+        visited.visit_cell(dir);
+    }
+    trace!("presents per house:\n{}", visited.render());
+
+    visited.cells.len()
+}
+
+fn count_houses_with_robot(moves: &[Direction]) -> usize {
     let mut visited_santa = Visited::new();
     let mut visited_robot = Visited::new();
-    let chars_santa = file_content.chars().step_by(2);
-    let chars_robot = file_content.chars().skip(1).step_by(2);
 
-    for inst in chars_santa {
-        visited_santa.parse_move(inst);
+    for &dir in moves.iter().step_by(2) {
+        visited_santa.visit_cell(dir);
     }
 
-    for inst in chars_robot {
-        visited_robot.parse_move(inst);
+    for &dir in moves.iter().skip(1).step_by(2) {
+        visited_robot.visit_cell(dir);
     }
 
-    let visited = for (key, value) in visited_robot.cells.iter() {
-        visited_santa
-            .cells
-            .entry(*key)
-            .and_modify(|v| *v += value)
-            .or_insert(*value);
-    };
+    for (point, visits) in visited_robot.cells.iter() {
+        *visited_santa.cells.get_mut(point) += visits;
+    }
 
-    let n_visited_more_then_once = visited_santa.cells.into_values().count();
-    println!("{:?}", n_visited_more_then_once);
+    visited_santa.cells.len()
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Direction>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_moves(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_houses(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(count_houses_with_robot(input).into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_parse_moves() {
+        assert_eq!(parse_moves("^>v<\n").unwrap().len(), 4);
+        let error = parse_moves("^>\n^x<").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), 2));
+    }
+
     #[test]
     fn test_visit_cell() {
         let mut visited = Visited::new();
-        visited.visit_cell(Direction::Right);
-        visited.visit_cell(Direction::Down);
-        visited.visit_cell(Direction::Down);
-        visited.visit_cell(Direction::Up);
-
-        assert_eq!(visited.cells.get(&Cell { x: 1, y: 0 }), Some(&1));
-        assert_eq!(visited.cells.get(&Cell { x: 1, y: -1 }), Some(&2));
-        assert_eq!(visited.cells.get(&Cell { x: 1, y: -11 }), None);
+        visited.visit_cell(Direction::East);
+        visited.visit_cell(Direction::South);
+        visited.visit_cell(Direction::South);
+        visited.visit_cell(Direction::North);
+
+        assert_eq!(*visited.cells.get(Point::new(1, 0)), 1);
+        assert_eq!(*visited.cells.get(Point::new(1, 1)), 2);
+        assert_eq!(*visited.cells.get(Point::new(1, -1)), 0);
+        assert_eq!(visited.render(), "11\n 2\n 1");
     }
 
     #[test]
     fn test_n_of_visited_more_then_once() {
         let mut visited1 = Visited::new();
-        visited1.visit_cell(Direction::Right);
-        visited1.visit_cell(Direction::Down);
-        visited1.visit_cell(Direction::Down);
-        visited1.visit_cell(Direction::Up);
-        visited1.visit_cell(Direction::Up);
+        visited1.visit_cell(Direction::East);
+        visited1.visit_cell(Direction::South);
+        visited1.visit_cell(Direction::South);
+        visited1.visit_cell(Direction::North);
+        visited1.visit_cell(Direction::North);
 
-        assert_eq!(visited1.cells.into_values().filter(|&n| n > 1).count(), 2);
+        assert_eq!(visited1.cells.values().filter(|&&n| n > 1).count(), 2);
 
         let mut visited2 = Visited::new();
-        visited2.visit_cell(Direction::Right);
-        visited2.visit_cell(Direction::Down);
-        visited2.visit_cell(Direction::Left);
-        visited2.visit_cell(Direction::Up);
+        visited2.visit_cell(Direction::East);
+        visited2.visit_cell(Direction::South);
+        visited2.visit_cell(Direction::West);
+        visited2.visit_cell(Direction::North);
 
-        assert_eq!(visited2.cells.into_values().filter(|&n| n > 1).count(), 1);
+        assert_eq!(visited2.cells.values().filter(|&&n| n > 1).count(), 1);
 
         let mut visited3 = Visited::new();
-        visited3.visit_cell(Direction::Right);
-        visited3.visit_cell(Direction::Left);
-        visited3.visit_cell(Direction::Right);
-        visited3.visit_cell(Direction::Left);
-        visited3.visit_cell(Direction::Right);
-        visited3.visit_cell(Direction::Left);
-        visited3.visit_cell(Direction::Right);
-        visited3.visit_cell(Direction::Left);
-        visited3.visit_cell(Direction::Right);
-        visited3.visit_cell(Direction::Left);
-
-        assert_eq!(visited3.cells.into_values().filter(|&n| n > 1).count(), 2);
+        visited3.visit_cell(Direction::East);
+        visited3.visit_cell(Direction::West);
+        visited3.visit_cell(Direction::East);
+        visited3.visit_cell(Direction::West);
+        visited3.visit_cell(Direction::East);
+        visited3.visit_cell(Direction::West);
+        visited3.visit_cell(Direction::East);
+        visited3.visit_cell(Direction::West);
+        visited3.visit_cell(Direction::East);
+        visited3.visit_cell(Direction::West);
+
+        assert_eq!(visited3.cells.values().filter(|&&n| n > 1).count(), 2);
     }
 }
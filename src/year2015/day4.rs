@@ -1,7 +1,8 @@
-use md5;
-use std::fs::read_to_string;
+use anyhow::Result;
 
-fn find_hashing(key: String, prefix: &str) -> i32 {
+use crate::solution::{Answer, Solution};
+
+fn find_hashing(key: &str, prefix: &str) -> i32 {
     for n in 0..100000000 {
         let data = format!("{}{}", key, n);
         let hashed = md5::compute(data);
@@ -14,9 +15,22 @@ fn find_hashing(key: String, prefix: &str) -> i32 {
     0
 }
 
-pub fn solve() {
-    let content = "bgvyzdsv".to_string();
-    println!("{:?}", find_hashing(content, "000000"));
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(find_hashing(input, "00000").into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(find_hashing(input, "000000").into())
+    }
 }
 
 #[cfg(test)]
@@ -25,8 +39,6 @@ mod test {
     fn test_start_with() {
         let hashed = md5::compute("abcdef609043");
 
-        println!("{:?}", "00000".as_bytes());
-
-        assert!(hashed.starts_with("00000".as_bytes()));
+        assert!(format!("{:x}", hashed).starts_with("00000"));
     }
 }
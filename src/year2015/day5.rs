@@ -1,18 +1,16 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::read_to_string,
     iter::zip,
 };
 
-use itertools::Itertools;
+use anyhow::Result;
+
+use crate::solution::{Answer, Solution};
 
 fn has_three_vowels(input: &str) -> bool {
     input
         .chars()
-        .filter(|c| match c {
-            'a' | 'e' | 'o' | 'i' | 'u' => true,
-            _ => false,
-        })
+        .filter(|c| matches!(c, 'a' | 'e' | 'o' | 'i' | 'u'))
         .count()
         >= 3
 }
@@ -67,13 +65,30 @@ fn has_pair_not_overlapping(input: &str) -> bool {
     false
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2015/Day5.txt").unwrap();
-    let res = content
-        .lines()
-        .filter(|l| has_two_separated(l) && has_pair_not_overlapping(l))
-        .count();
-    println!("{:?}", res);
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(input
+            .lines()
+            .filter(|l| has_three_vowels(l) && has_two_in_row(l) && has_not_forbidden(l))
+            .count()
+            .into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(input
+            .lines()
+            .filter(|l| has_two_separated(l) && has_pair_not_overlapping(l))
+            .count()
+            .into())
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,17 @@
 use std::str::FromStr;
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use regex::Regex;
 
-type Grid = Vec<Vec<bool>>;
+use crate::{
+    geometry::Point,
+    grid::{Grid, Rect},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
-type Grid2 = Vec<Vec<i32>>;
+/// The width and height of the grid of lights.
+const GRID_SIZE: usize = 1000;
 
 #[derive(Debug, PartialEq, Eq)]
 enum Action {
@@ -15,87 +21,99 @@ enum Action {
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     action: Action,
-    start: (usize, usize),
-    end: (usize, usize),
+    start: Point<usize>,
+    end: Point<usize>,
 }
 
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let re = Regex::new(
             r"^(?P<action>turn on|turn off|toggle) (?P<start1>\d+),(?P<start2>\d+) through (?P<end1>\d+),(?P<end2>\d+)$",
-        )?;
-        let caps = re.captures(s).ok_or(anyhow!("Invalid instruction"))?;
-        let action = match caps.name("action").unwrap().as_str() {
+        )
+        .expect("instruction pattern is valid");
+        let caps = re.captures(s).ok_or_else(|| {
+            ParseError::new(s, s, "`turn on|turn off|toggle <x>,<y> through <x>,<y>`")
+        })?;
+        let action = match &caps["action"] {
             "turn on" => Action::TurnOn,
             "turn off" => Action::TurnOff,
-            "toggle" => Action::Toggle,
-            _ => return Err(anyhow!("Invalid action")),
+            _ => Action::Toggle,
         };
-        let start = (
-            caps.name("start1").unwrap().as_str().parse()?,
-            caps.name("start2").unwrap().as_str().parse()?,
-        );
-        let end = (
-            caps.name("end1").unwrap().as_str().parse()?,
-            caps.name("end2").unwrap().as_str().parse()?,
-        );
+        let coordinate = |name| {
+            let text = caps.name(name).unwrap().as_str();
+            match parse::number(s, text)? {
+                n if n < GRID_SIZE => Ok(n),
+                _ => Err(ParseError::new(
+                    s,
+                    text,
+                    format!("a coordinate below {}", GRID_SIZE),
+                )),
+            }
+        };
+        let start = Point::new(coordinate("start1")?, coordinate("start2")?);
+        let end = Point::new(coordinate("end1")?, coordinate("end2")?);
         Ok(Instruction { action, start, end })
     }
 }
 
-fn apply_instruction(grid: &mut Grid, instruction: &Instruction) {
-    for i in instruction.start.0..=instruction.end.0 {
-        for j in instruction.start.1..=instruction.end.1 {
-            match instruction.action {
-                Action::TurnOn => grid[i][j] = true,
-                Action::TurnOff => grid[i][j] = false,
-                Action::Toggle => grid[i][j] = !grid[i][j],
-            }
-        }
+impl Instruction {
+    fn rect(&self) -> Rect {
+        Rect::spanning(self.start, self.end)
     }
 }
 
-fn apply_instruction2(grid: &mut Grid2, instruction: &Instruction) {
-    for i in instruction.start.0..=instruction.end.0 {
-        for j in instruction.start.1..=instruction.end.1 {
-            match instruction.action {
-                Action::TurnOn => grid[i][j] += 1,
-                Action::TurnOff => {
-                    if grid[i][j] > 0 {
-                        grid[i][j] -= 1
-                    }
-                }
-                Action::Toggle => grid[i][j] += 2,
-            }
-        }
+fn apply_instruction(grid: &mut Grid<bool>, instruction: &Instruction) {
+    match instruction.action {
+        Action::TurnOn => grid.fill_rect(instruction.rect(), true),
+        Action::TurnOff => grid.fill_rect(instruction.rect(), false),
+        Action::Toggle => grid.map_rect(instruction.rect(), |&light| !light),
     }
 }
 
-fn apply_instructions(input: &str) -> usize {
-    let mut grid = vec![vec![false; 1000]; 1000];
-    for line in input.lines() {
-        let instruction = line.parse::<Instruction>().unwrap();
-        apply_instruction(&mut grid, &instruction);
+fn apply_instruction2(grid: &mut Grid<i32>, instruction: &Instruction) {
+    grid.map_rect(instruction.rect(), |&light| match instruction.action {
+        Action::TurnOn => light + 1,
+        Action::TurnOff => (light - 1).max(0),
+        Action::Toggle => light + 2,
+    });
+}
+
+fn apply_instructions(instructions: &[Instruction]) -> usize {
+    let mut grid = Grid::new(GRID_SIZE, GRID_SIZE, false);
+    for instruction in instructions {
+        apply_instruction(&mut grid, instruction);
     }
-    grid.iter().flatten().filter(|&&x| x).count()
+    grid.values().filter(|&&x| x).count()
 }
 
-fn apply_instructions2(input: &str) -> i32 {
-    let mut grid = vec![vec![0; 1000]; 1000];
-    for line in input.lines() {
-        let instruction = line.parse::<Instruction>().unwrap();
-        apply_instruction2(&mut grid, &instruction);
+fn apply_instructions2(instructions: &[Instruction]) -> i32 {
+    let mut grid = Grid::new(GRID_SIZE, GRID_SIZE, 0);
+    for instruction in instructions {
+        apply_instruction2(&mut grid, instruction);
     }
-    grid.iter().flatten().sum()
+    grid.values().sum()
 }
 
-pub fn solve() {
-    let content = std::fs::read_to_string("inputs/Year2015/Day6.txt").unwrap();
-    println!("{}", apply_instructions2(&content));
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::parse_lines(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(apply_instructions(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(apply_instructions2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -107,14 +125,24 @@ mod test {
         let input = "turn on 0,0 through 999,999";
         let instruction = input.parse::<Instruction>().unwrap();
         assert_eq!(instruction.action, Action::TurnOn);
-        assert_eq!(instruction.start, (0, 0));
-        assert_eq!(instruction.end, (999, 999));
+        assert_eq!(instruction.start, Point::new(0, 0));
+        assert_eq!(instruction.end, Point::new(999, 999));
+
+        let error = "turn up 0,0 through 1,1"
+            .parse::<Instruction>()
+            .unwrap_err();
+        assert_eq!(error.column, 1);
+
+        let error = "toggle 0,0 through 1000,5"
+            .parse::<Instruction>()
+            .unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (20, "1000"));
     }
 
     #[test]
     fn test_apply_instructions2() {
         let input = "turn on 0,0 through 10,10\n\
                      turn on 0,0 through 10,10";
-        assert_eq!(apply_instructions2(input), 242);
+        assert_eq!(apply_instructions2(&Day6::parse(input).unwrap()), 242);
     }
 }
@@ -1,5 +1,13 @@
 use anyhow::{anyhow, Result};
-use std::{borrow::Cow, collections::HashMap, fs::read_to_string, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    combinator::{self, identifier, integer, literal, take_while1, Parser},
+    explore::{self, Explorer, Helper},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+    trace,
+};
 
 type Wire = String;
 
@@ -11,62 +19,43 @@ struct Instruction {
     operation: Operation,
 }
 
+fn operand<'a>() -> impl Parser<'a, Wire> {
+    take_while1("a wire or a signal", |c| c.is_ascii_alphanumeric()).map(str::to_string)
+}
+
+fn operation<'a>() -> impl Parser<'a, Operation> {
+    let and = operand().then_ignore(literal(" AND ")).then(operand());
+    let or = operand().then_ignore(literal(" OR ")).then(operand());
+    let lshift = operand().then_ignore(literal(" LSHIFT ")).then(integer());
+    let rshift = operand().then_ignore(literal(" RSHIFT ")).then(integer());
+    and.map(|(a, b)| Operation::And(a, b))
+        .or(or.map(|(a, b)| Operation::Or(a, b)))
+        .or(lshift.map(|(a, n)| Operation::LShift(a, n)))
+        .or(rshift.map(|(a, n)| Operation::RShift(a, n)))
+        .or(literal("NOT ").ignore_then(operand()).map(Operation::Not))
+        .or(operand().map(Operation::Assign))
+}
+
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    operation()
+        .then_ignore(literal(" -> "))
+        .then(identifier().labelled("an output wire"))
+        .map(|(operation, output)| Instruction {
+            output: output.to_string(),
+            operation,
+        })
+}
+
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self> {
-        let parts: Vec<&str> = s.split(" -> ").collect();
-        let output = parts[1].to_string();
-        if parts[0].contains("AND") {
-            let operands: Vec<&str> = parts[0].split(" AND ").collect();
-            let input1 = operands[0].to_string();
-            let input2 = operands[1].to_string();
-            return Ok(Instruction {
-                output,
-                operation: Operation::And(input1, input2),
-            });
-        } else if parts[0].contains("OR") {
-            let operands: Vec<&str> = parts[0].split(" OR ").collect();
-            let input1 = operands[0].to_string();
-            let input2 = operands[1].to_string();
-            return Ok(Instruction {
-                output,
-                operation: Operation::Or(input1, input2),
-            });
-        } else if parts[0].contains("LSHIFT") {
-            let operands: Vec<&str> = parts[0].split(" LSHIFT ").collect();
-            let input = operands[0].to_string();
-            let shift = operands[1].parse()?;
-            return Ok(Instruction {
-                output: output,
-                operation: Operation::LShift(input, shift),
-            });
-        } else if parts[0].contains("RSHIFT") {
-            let operands: Vec<&str> = parts[0].split(" RSHIFT ").collect();
-            let input = operands[0].to_string();
-            let shift = operands[1].parse()?;
-            return Ok(Instruction {
-                output,
-                operation: Operation::RShift(input, shift),
-            });
-        } else if parts[0].contains("NOT") {
-            let input = parts[0].replace("NOT ", "");
-            return Ok(Instruction {
-                output,
-                operation: Operation::Not(input),
-            });
-        } else {
-            let input = parts[0].parse()?;
-            Ok(Instruction {
-                output,
-                operation: Operation::Assign(input),
-            })
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        combinator::parse_line(instruction(), s)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Operation {
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Operation {
     And(Wire, Wire),
     Or(Wire, Wire),
     LShift(Wire, u16),
@@ -75,13 +64,11 @@ enum Operation {
     Assign(Wire),
 }
 
-fn parse_input(input: &str) -> Result<Circuit> {
-    let mut circuit = Circuit::new();
-    for line in input.lines() {
-        let instruction = line.parse::<Instruction>()?;
-        circuit.insert(instruction.output, instruction.operation);
-    }
-    Ok(circuit)
+fn parse_input(input: &str) -> Result<Circuit, ParseError> {
+    Ok(parse::parse_lines::<Instruction>(input)?
+        .into_iter()
+        .map(|instruction| (instruction.output, instruction.operation))
+        .collect())
 }
 
 fn evaluate_wire(circuit: &Circuit, wire: &Wire, cache: &mut HashMap<String, u16>) -> Result<u16> {
@@ -91,7 +78,7 @@ fn evaluate_wire(circuit: &Circuit, wire: &Wire, cache: &mut HashMap<String, u16
     if let Some(value) = cache.get(wire) {
         return Ok(*value);
     };
-    println!("Evaluating wire: {} with operation: {:?}", wire, operation);
+    trace!("evaluating wire {} = {:?}", wire, operation);
     let result = match operation {
         Operation::Assign(value) => {
             if let Ok(value) = value.parse() {
@@ -146,20 +133,58 @@ fn evaluate_wire(circuit: &Circuit, wire: &Wire, cache: &mut HashMap<String, u16
     Ok(value)
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2015/Day7.txt").unwrap();
-    let mut circuit = parse_input(&content).unwrap();
-    circuit.insert("b".to_string(), Operation::Assign("16076".to_string()));
-    let mut cache = HashMap::new();
-    println!(
-        "Part 1: {:?}",
-        evaluate_wire(&circuit, &"a".to_string(), &mut cache)
-    );
+fn explore_wire(input: &str, wire: &str) -> Result<String> {
+    let circuit = parse_input(input)?;
+    let value = evaluate_wire(&circuit, &wire.to_string(), &mut HashMap::new())?;
+    Ok(format!("{} = {}", wire, value))
+}
+
+pub const EXPLORER: Explorer = Explorer {
+    parses: "Instruction",
+    parse_line: explore::describe::<Instruction>,
+    helpers: &[Helper {
+        name: "wire",
+        usage: "<wire>",
+        help: "evaluate a wire of the circuit",
+        run: explore_wire,
+    }],
+};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Circuit;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let mut cache = HashMap::new();
+        Ok(evaluate_wire(input, &"a".to_string(), &mut cache)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let a = evaluate_wire(input, &"a".to_string(), &mut HashMap::new())?;
+        let mut circuit = input.clone();
+        circuit.insert("b".to_string(), Operation::Assign(a.to_string()));
+        let mut cache = HashMap::new();
+        Ok(evaluate_wire(&circuit, &"a".to_string(), &mut cache)?.into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::solution::examples;
+
+    const CIRCUIT: &str = "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i\nd -> z\n1 AND x -> a";
+
+    // The example has no `b` wire, so overriding it leaves `a` unchanged.
+    examples!(Day7 {
+        circuit: CIRCUIT => part1: 1, part2: 1;
+    });
+
     #[test]
     fn test_parse_instruction() {
         let input = "123 -> x";
@@ -183,13 +208,16 @@ mod test {
             instruction.operation,
             Operation::LShift("bi".to_string(), 15),
         );
+
+        let error = "x LSHIFT y -> z".parse::<Instruction>().unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (10, "y"));
+        let error = "x AND y -> ".parse::<Instruction>().unwrap_err();
+        assert_eq!(error.expected, "an output wire");
     }
 
     #[test]
     fn test_parse_input() {
-        let input = "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i\nd -> z\n1 AND x -> a";
-
-        let circuit = parse_input(input).unwrap();
+        let circuit = parse_input(CIRCUIT).unwrap();
 
         assert_eq!(circuit.len(), 10);
         assert_eq!(
@@ -212,9 +240,7 @@ mod test {
 
     #[test]
     fn test_evalute_wire() {
-        let input = "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i\nd -> z\n1 AND x -> a";
-
-        let circuit = parse_input(input).unwrap();
+        let circuit = parse_input(CIRCUIT).unwrap();
         let mut cache = HashMap::new();
 
         assert_eq!(
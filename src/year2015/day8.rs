@@ -1,5 +1,6 @@
-use itertools::Itertools;
-use std::fs::read_to_string;
+use anyhow::Result;
+
+use crate::solution::{Answer, Solution};
 
 fn part1(s: &str) -> (usize, usize) {
     let mut chars_data = 0;
@@ -39,8 +40,7 @@ fn part2(s: &str) -> (usize, usize) {
     let mut chars_data = 2;
     let mut chars_code = 0;
 
-    let mut chars = s.chars().peekable();
-    while let Some(c) = chars.next() {
+    for c in s.chars() {
         chars_code += 1;
 
         match c {
@@ -59,18 +59,36 @@ fn part2(s: &str) -> (usize, usize) {
     (chars_data, chars_code)
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2015/Day8.txt").unwrap();
-    let result = content
-        .lines()
-        .map(part2)
-        .fold((0, 0), |acc, (data, code)| (acc.0 + data, acc.1 + code));
-    println!("Part 1: {}", result.0 - result.1);
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let (data, code) = input
+            .lines()
+            .map(part1)
+            .fold((0, 0), |acc, (data, code)| (acc.0 + data, acc.1 + code));
+        Ok((code - data).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (encoded, code) = input
+            .lines()
+            .map(part2)
+            .fold((0, 0), |acc, (data, code)| (acc.0 + data, acc.1 + code));
+        Ok((encoded - code).into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use itertools::Itertools;
     static INPUT: &str = r#"""
 "abc"
 "aaa\"aaa"
@@ -1,6 +1,13 @@
-use std::{collections::HashMap, fs::read_to_string, str::FromStr};
+use std::str::FromStr;
 
-use itertools::Itertools;
+use anyhow::{anyhow, Result};
+
+use crate::{
+    graph::{Graph, Objective},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+    trace,
+};
 
 type City = String;
 
@@ -11,81 +18,78 @@ struct Route {
     distance: usize,
 }
 
-type RouteMap = HashMap<(City, City), usize>;
-
 impl FromStr for Route {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(" = ").collect();
-        let cities: Vec<&str> = parts[0].split(" to ").collect();
-        let from = cities[0].to_string();
-        let to = cities[1].to_string();
-        let distance = parts[1].parse().unwrap();
-        Ok(Route { from, to, distance })
+        let (cities, distance) = s
+            .split_once(" = ")
+            .ok_or_else(|| ParseError::end(s, "` = <distance>`"))?;
+        let (from, to) = cities
+            .split_once(" to ")
+            .ok_or_else(|| ParseError::new(s, cities, "`<city> to <city>`"))?;
+        let distance = parse::number(s, distance)?;
+        Ok(Route {
+            from: from.to_string(),
+            to: to.to_string(),
+            distance,
+        })
     }
 }
 
-fn build_route_map(s: &str) -> RouteMap {
-    let routes = s
-        .lines()
-        .map(|line| line.parse::<Route>().unwrap())
-        .collect_vec();
-    let mut route_map = RouteMap::new();
-    for route in routes {
-        route_map.insert((route.from.clone(), route.to.clone()), route.distance);
-        route_map.insert((route.to, route.from), route.distance);
-    }
-    route_map
+fn build_graph(s: &str) -> Result<Graph, ParseError> {
+    let routes: Vec<Route> = parse::parse_lines(s)?;
+    Ok(Graph::undirected(routes.iter().map(|r| {
+        (r.from.as_str(), r.to.as_str(), r.distance as i64)
+    })))
 }
 
-fn find_shortest(route_map: &RouteMap) -> usize {
-    let cities: Vec<City> = route_map
-        .keys()
-        .map(|(from, to)| vec![from.clone(), to.clone()])
-        .flatten()
-        .unique()
-        .collect();
-    let mut shortest = usize::MAX;
-    for permutation in cities.iter().permutations(cities.len()) {
-        let mut distance = 0;
-        for i in 0..permutation.len() - 1 {
-            distance += route_map[&(permutation[i].clone(), permutation[i + 1].clone())];
-        }
-        if distance < shortest {
-            shortest = distance;
-        }
-    }
-    shortest
+/// The length of the best route visiting every city once.
+fn find_route(graph: &Graph, objective: Objective) -> Result<i64> {
+    let route = graph
+        .hamiltonian_path(objective)
+        .ok_or_else(|| anyhow!("no route visits every city"))?;
+    trace!(
+        "{:?} route: {} = {}",
+        objective,
+        graph.names(&route).collect::<Vec<_>>().join(" -> "),
+        route.length
+    );
+    Ok(route.length)
 }
 
-fn find_longest(route_map: &RouteMap) -> usize {
-    let cities: Vec<City> = route_map
-        .keys()
-        .map(|(from, to)| vec![from.clone(), to.clone()])
-        .flatten()
-        .unique()
-        .collect();
-    let mut longest = usize::MIN;
-    for permutation in cities.iter().permutations(cities.len()) {
-        let mut distance = 0;
-        for i in 0..permutation.len() - 1 {
-            distance += route_map[&(permutation[i].clone(), permutation[i + 1].clone())];
-        }
-        if distance > longest {
-            longest = distance;
-        }
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Graph;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(build_graph(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(find_route(input, Objective::Min)?.into())
     }
-    longest
-}
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2015/Day9.txt").unwrap();
-    println!("{:?}", find_longest(&build_route_map(&content)));
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(find_route(input, Objective::Max)?.into())
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
+    const EXAMPLE: &str =
+        "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141";
+
+    #[test]
+    fn test_find_route() {
+        let graph = build_graph(EXAMPLE).unwrap();
+        assert_eq!(find_route(&graph, Objective::Min).unwrap(), 605);
+        assert_eq!(find_route(&graph, Objective::Max).unwrap(), 982);
+    }
+
     #[test]
     fn test_parse_route() {
         let input = "London to Dublin = 464";
@@ -93,5 +97,11 @@ mod test {
         assert_eq!(route.from, "London");
         assert_eq!(route.to, "Dublin");
         assert_eq!(route.distance, 464);
+
+        let error = "London to Dublin = far"
+            .parse::<super::Route>()
+            .unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (20, "far"));
+        assert!("London Dublin = 464".parse::<super::Route>().is_err());
     }
 }
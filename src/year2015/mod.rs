@@ -1 +1,12 @@
+pub mod day1;
 pub mod day10;
+pub mod day2;
+pub mod day3;
+#[cfg(feature = "hashing")]
+pub mod day4;
+pub mod day5;
+#[cfg(feature = "regex")]
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
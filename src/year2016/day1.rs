@@ -1,68 +1,30 @@
-use std::{
-    char::ParseCharError, collections::HashSet, fs::read_to_string, num::ParseIntError,
-    str::FromStr,
-};
-
-use itertools::{concat, Itertools};
-
-#[derive(Debug, PartialEq, Eq)]
-enum Turn {
-    Left,
-    Right,
-}
+use std::str::FromStr;
 
-impl FromStr for Turn {
-    type Err = &'static str;
+use anyhow::{anyhow, Result};
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "L" => Ok(Turn::Left),
-            "R" => Ok(Turn::Right),
-            _ => Err("wrong turn in instruction"),
-        }
-    }
-}
+use crate::{
+    geometry::{Direction, Point, Turn},
+    grid::SparseGrid,
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+    trace,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-impl Direction {
-    fn get_new(direction: &Self, turn: Turn) -> Self {
-        match turn {
-            Turn::Left => match direction {
-                Direction::Left => Direction::Down,
-                Direction::Right => Direction::Up,
-                Direction::Down => Direction::Right,
-                Direction::Up => Direction::Left,
-            },
-            Turn::Right => match direction {
-                Direction::Left => Direction::Up,
-                Direction::Down => Direction::Left,
-                Direction::Right => Direction::Down,
-                Direction::Up => Direction::Right,
-            },
-        }
-    }
-}
-
-#[derive(Debug, PartialEq, Eq)]
-struct Instruction {
+pub struct Instruction {
     turn: Turn,
-    steps: i32,
+    steps: u32,
 }
 
 impl FromStr for Instruction {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (turn_str, steps_str) = s.split_at(1);
-        let turn = turn_str.parse::<Turn>()?;
-        let steps = steps_str.parse::<i32>().map_err(|_| "invalid steps")?;
+        let (turn_str, steps_str) = s.split_at(s.chars().next().map_or(0, char::len_utf8));
+        let turn = turn_str
+            .parse::<Turn>()
+            .map_err(|e| e.within(s, turn_str))?;
+        let steps = parse::number(s, steps_str)?;
 
         Ok(Instruction { turn, steps })
     }
@@ -71,147 +33,135 @@ impl FromStr for Instruction {
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Position {
     direction: Direction,
-    x: i32,
-    y: i32,
-    visited: HashSet<(i32, i32)>,
+    location: Point<i32>,
+    /// How many times each block was walked through.
+    visited: SparseGrid<usize>,
 }
 
 impl Default for Position {
     fn default() -> Self {
-        let mut visited = HashSet::new();
-        visited.insert((0, 0));
-
-        Position {
-            direction: Direction::Up,
-            x: 0,
-            y: 0,
-            visited,
-        }
+        Position::new(Direction::North, Point::ORIGIN)
     }
 }
 
 impl Position {
-    fn new(direction: Direction, x: i32, y: i32) -> Self {
-        let mut visited = HashSet::new();
-        visited.insert((0, 0));
-
+    fn new(direction: Direction, location: Point<i32>) -> Self {
         Position {
             direction,
-            x,
-            y,
-            visited,
+            location,
+            visited: [Point::ORIGIN].into_iter().collect(),
         }
     }
 
-    fn get_visited(&self, direction: &Direction, steps: i32) -> Vec<(i32, i32)> {
-        let (start_x, start_y) = (self.x, self.y);
-        let mut points = Vec::new();
-
-        match direction {
-            Direction::Down => {
-                for step in 1..=steps {
-                    points.push((start_x, start_y - step));
-                }
-            }
-            Direction::Up => {
-                for step in 1..=steps {
-                    points.push((start_x, start_y + step));
-                }
-            }
-            Direction::Left => {
-                for step in 1..=steps {
-                    points.push((start_x - step, start_y));
-                }
-            }
-            Direction::Right => {
-                for step in 1..=steps {
-                    points.push((start_x + step, start_y));
-                }
-            }
-        }
-
-        points
+    fn get_visited(&self, direction: Direction, steps: u32) -> Vec<Point<i32>> {
+        self.location.walk(direction).take(steps as usize).collect()
     }
 
-    fn update(&mut self, instruction: Instruction) -> Vec<(i32, i32)> {
-        let new_dir = Direction::get_new(&self.direction, instruction.turn);
-        let visited = self.get_visited(&new_dir, instruction.steps);
-
-        let (x, y) = match new_dir {
-            Direction::Down => (self.x, self.y - instruction.steps),
-            Direction::Up => (self.x, self.y + instruction.steps),
-            Direction::Right => (self.x + instruction.steps, self.y),
-            Direction::Left => (self.x - instruction.steps, self.y),
-        };
-
-        self.x = x;
-        self.y = y;
-        self.direction = new_dir;
-
-        for (x, y) in visited.clone() {
-            self.visited.insert((x, y));
+    fn update(&mut self, instruction: Instruction) -> Vec<Point<i32>> {
+        self.direction = self.direction.turn(instruction.turn);
+        let visited = self.get_visited(self.direction, instruction.steps);
+        if let Some(&last) = visited.last() {
+            self.location = last;
+        }
+        for &point in &visited {
+            self.visited.visit(point);
         }
 
         visited
     }
 }
 
-fn get_instructions(s: &str) -> Vec<Instruction> {
-    s.lines()
-        .collect_vec()
-        .get(0)
-        .unwrap()
-        .split(", ")
-        .map(|inst| inst.parse().unwrap())
-        .collect_vec()
+fn get_instructions(s: &str) -> Result<Vec<Instruction>, ParseError> {
+    let line = s.lines().next().unwrap_or_default();
+    line.split(", ")
+        .map(|inst| {
+            inst.parse::<Instruction>()
+                .map_err(|e| e.within(line, inst).at_line(1))
+        })
+        .collect()
 }
 
 fn get_distance(position: Position) -> i32 {
-    position.x.abs() + position.y.abs()
+    position.location.manhattan(Point::ORIGIN)
 }
 
-// fn get_final_position(instructions: Vec<Instruction>) -> Position {
-//     let mut curr_pos = Position::default();
-//     for instruction in instructions {
-//         curr_pos.update(instruction);
-//     }
+fn get_final_position(instructions: &[Instruction]) -> Position {
+    let mut curr_pos = Position::default();
+    for &instruction in instructions {
+        curr_pos.update(instruction);
+    }
+
+    curr_pos
+}
 
-//     curr_pos
-// }
+/// Draws the blocks walked through, marking the start with `S` and `revisit`
+/// with `X`.
+fn render_path(position: &Position, revisit: Point<i32>) -> String {
+    position.visited.render(|point, visits| match visits {
+        _ if point == revisit => 'X',
+        _ if point == Point::ORIGIN => 'S',
+        Some(_) => '#',
+        None => ' ',
+    })
+}
 
-fn get_final_position(instructions: Vec<Instruction>) -> Position {
+fn find_first_revisited(instructions: &[Instruction]) -> Option<Point<i32>> {
     let mut curr_pos = Position::default();
-    for instruction in instructions {
-        let mut new_pos = curr_pos.clone();
-        let visited = new_pos.update(instruction);
-        if visited.iter().any(|coord| curr_pos.visited.contains(coord)) {
-            break;
-        };
-        curr_pos = new_pos;
+    for &instruction in instructions {
+        let new_dir = curr_pos.direction.turn(instruction.turn);
+        let path = curr_pos.get_visited(new_dir, instruction.steps);
+        if let Some(&point) = path.iter().find(|&&point| curr_pos.visited.contains(point)) {
+            trace!(
+                "path up to the first revisit:\n{}",
+                render_path(&curr_pos, point)
+            );
+            return Some(point);
+        }
+        curr_pos.update(instruction);
     }
 
-    curr_pos
+    None
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day1.txt").unwrap();
-    println!(
-        "{:?}",
-        get_distance(get_final_position(get_instructions(content.as_str())))
-    );
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(get_instructions(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(get_distance(get_final_position(input)).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let point =
+            find_first_revisited(input).ok_or_else(|| anyhow!("no location is visited twice"))?;
+        Ok(point.manhattan(Point::ORIGIN).into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::solution::examples;
+
+    examples!(Day1 {
+        right_left: "R2, L3" => part1: 5;
+        half_circle: "R2, R2, R2" => part1: 2;
+        loop_back: "R5, L5, R5, R3" => part1: 12;
+        revisit: "R8, R4, R4, R8" => part2: 4;
+    });
 
     #[test]
     fn test_update_position() {
-        let mut pos = Position::new(Direction::Up, 0, 0);
-        let mut visited = HashSet::new();
-        visited.insert((0, 0));
-        visited.insert((-1, 0));
-        visited.insert((-2, 0));
+        let mut pos = Position::new(Direction::North, Point::ORIGIN);
+        let mut visited: SparseGrid<usize> =
+            [Point::new(0, 0), Point::new(-1, 0), Point::new(-2, 0)]
+                .into_iter()
+                .collect();
         pos.update(Instruction {
             turn: Turn::Left,
             steps: 2,
@@ -219,9 +169,8 @@ mod test {
         assert_eq!(
             pos,
             Position {
-                direction: Direction::Left,
-                x: -2,
-                y: 0,
+                direction: Direction::West,
+                location: Point::new(-2, 0),
                 visited: visited.clone()
             }
         );
@@ -233,15 +182,14 @@ mod test {
             turn: Turn::Right,
             steps: 1,
         });
-        visited.insert((-2, 1));
-        visited.insert((-2, 2));
-        visited.insert((-1, 2));
+        for point in [Point::new(-2, -1), Point::new(-2, -2), Point::new(-1, -2)] {
+            visited.visit(point);
+        }
         assert_eq!(
             pos,
             Position {
-                direction: Direction::Right,
-                x: -1,
-                y: 2,
+                direction: Direction::East,
+                location: Point::new(-1, -2),
                 visited: visited.clone()
             }
         );
@@ -269,9 +217,23 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_instruction_errors() {
+        let error = get_instructions("L5, X1").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (5, "X"));
+        let error = get_instructions("L5, R1x").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (6, "1x"));
+        assert_eq!(error.context, "L5, R1x");
+        let error = get_instructions("R-3").unwrap_err();
+        assert_eq!(
+            (error.column, error.text.as_str(), error.expected.as_str()),
+            (2, "-3", "a number")
+        );
+    }
+
     #[test]
     fn test_get_instructions() {
-        let insts = get_instructions("L5, R1, R4, L5, L4, R3\n");
+        let insts = get_instructions("L5, R1, R4, L5, L4, R3\n").unwrap();
 
         assert_eq!(
             insts,
@@ -303,4 +265,17 @@ mod test {
             ]
         )
     }
+
+    #[test]
+    fn test_render_path() {
+        let instructions = get_instructions("R8, R4, R4, R8").unwrap();
+        let mut position = Position::default();
+        for &instruction in &instructions[..3] {
+            position.update(instruction);
+        }
+        assert_eq!(
+            render_path(&position, Point::new(4, 0)),
+            "S###X####\n        #\n        #\n        #\n    #####"
+        );
+    }
 }
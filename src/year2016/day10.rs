@@ -1,6 +1,13 @@
-use std::fs::read_to_string;
+use anyhow::Result;
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day10.txt").unwrap();
-    println!("{}", content)
+use crate::solution::Solution;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
 }
@@ -1,97 +1,72 @@
-use std::{fs::read_to_string, str::FromStr, vec};
+use anyhow::Result;
 
-use itertools::{Itertools, Position};
+use crate::{
+    geometry::{self, Direction, Point},
+    parse::ParseError,
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 struct Keypad {
-    pos: (usize, usize),
+    pos: Point<usize>,
     keypad: Vec<Vec<i8>>,
 }
 
 impl Keypad {
     fn new() -> Self {
         Keypad {
-            pos: (1, 1),
+            pos: Point::new(1, 1),
             keypad: vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]],
         }
     }
 
     fn update_pos(&mut self, direction: Direction) {
-        let mut new_x = self.pos.0;
-        let mut new_y = self.pos.1;
-        match direction {
-            Direction::Up => match self.pos {
-                (_, 0) => (),
-                _ => new_y = new_y - 1,
-            },
-            Direction::Down => match self.pos {
-                (_, 2) => (),
-                _ => new_y = new_y + 1,
-            },
-            Direction::Left => match self.pos {
-                (0, _) => (),
-                _ => new_x = new_x - 1,
-            },
-            Direction::Right => match self.pos {
-                (2, _) => (),
-                _ => new_x = new_x + 1,
-            },
-        }
-
-        self.pos = (new_x, new_y);
-    }
-}
-
-#[derive(Debug, PartialEq, Eq)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-impl FromStr for Direction {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "R" => Ok(Self::Right),
-            "L" => Ok(Self::Left),
-            "D" => Ok(Self::Down),
-            "U" => Ok(Self::Up),
-            _ => Err("bad character"),
+        if let Some(pos) = self
+            .pos
+            .checked_step(direction)
+            .filter(|p| p.x < 3 && p.y < 3)
+        {
+            self.pos = pos;
         }
     }
 }
 
-fn parse_instructions(input: &str) -> Vec<Direction> {
-    input
-        .chars()
-        .map(|c| c.to_string().parse().unwrap())
-        .collect_vec()
+fn parse_instructions(line: &str) -> Result<Vec<Direction>, ParseError> {
+    geometry::parse_chars(line, "one of U, D, L, R", Direction::from_udlr)
 }
 
-fn get_code(input: &str) -> String {
-    let instructions_lines = input.lines().map(|l| parse_instructions(l));
-
+fn get_code(instructions_lines: &[Vec<Direction>]) -> String {
     let mut result = String::new();
 
     for line in instructions_lines {
         let mut keypad = Keypad::new();
-        for instruction in line {
+        for &instruction in line {
             keypad.update_pos(instruction);
         }
 
-        let final_digit = keypad.keypad[keypad.pos.1][keypad.pos.0];
+        let final_digit = keypad.keypad[keypad.pos.y][keypad.pos.x];
         result.push(char::from_digit(final_digit as u32, 10).unwrap());
     }
 
     result
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day2.txt").unwrap();
-    println!("{:?}", get_code(content.as_str()));
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<Direction>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .enumerate()
+            .map(|(n, line)| parse_instructions(line).map_err(|e| e.at_line(n + 1)))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(get_code(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -101,13 +76,13 @@ mod test {
     #[test]
     fn test_parse_instructions() {
         assert_eq!(
-            parse_instructions("RDRRD"),
+            parse_instructions("RDRRD").unwrap(),
             vec![
-                Direction::Right,
-                Direction::Down,
-                Direction::Right,
-                Direction::Right,
-                Direction::Down
+                Direction::East,
+                Direction::South,
+                Direction::East,
+                Direction::East,
+                Direction::South
             ]
         )
     }
@@ -115,27 +90,33 @@ mod test {
     #[test]
     fn test_update_pos() {
         let mut keypad = Keypad::new();
-        keypad.update_pos(Direction::Down);
-        assert_eq!(keypad.pos, (1, 2));
-
-        keypad.update_pos(Direction::Right);
-        keypad.update_pos(Direction::Right);
-        assert_eq!(keypad.pos, (2, 2));
-
-        keypad.update_pos(Direction::Up);
-        keypad.update_pos(Direction::Up);
-        keypad.update_pos(Direction::Up);
-        assert_eq!(keypad.pos, (2, 0));
-
-        keypad.update_pos(Direction::Left);
-        keypad.update_pos(Direction::Left);
-        keypad.update_pos(Direction::Left);
-        assert_eq!(keypad.pos, (0, 0));
+        keypad.update_pos(Direction::South);
+        assert_eq!(keypad.pos, Point::new(1, 2));
+
+        keypad.update_pos(Direction::East);
+        keypad.update_pos(Direction::East);
+        assert_eq!(keypad.pos, Point::new(2, 2));
+
+        keypad.update_pos(Direction::North);
+        keypad.update_pos(Direction::North);
+        keypad.update_pos(Direction::North);
+        assert_eq!(keypad.pos, Point::new(2, 0));
+
+        keypad.update_pos(Direction::West);
+        keypad.update_pos(Direction::West);
+        keypad.update_pos(Direction::West);
+        assert_eq!(keypad.pos, Point::new(0, 0));
     }
 
     #[test]
     fn test_get_code() {
-        assert_eq!(get_code("DRRUUULLL\n"), "1".to_string());
-        assert_eq!(get_code("DDDLLUUUURD\n"), "5".to_string());
+        assert_eq!(
+            get_code(&Day2::parse("DRRUUULLL\n").unwrap()),
+            "1".to_string()
+        );
+        assert_eq!(
+            get_code(&Day2::parse("DDDLLUUUURD\n").unwrap()),
+            "5".to_string()
+        );
     }
 }
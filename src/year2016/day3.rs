@@ -1,6 +1,11 @@
-use std::{fs::read_to_string, str::FromStr};
+use std::str::FromStr;
 
-use itertools::Itertools;
+use anyhow::Result;
+
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 struct Triangle {
@@ -14,25 +19,28 @@ impl Triangle {
 }
 
 impl FromStr for Triangle {
-    type Err = &'static str;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut sides = s
+        let mut sides: Vec<usize> = s
             .split_whitespace()
-            .map(|side| side.parse().expect("Bad side"))
-            .collect_vec();
+            .map(|side| parse::number(s, side))
+            .collect::<Result<_, _>>()?;
+        if sides.len() != 3 {
+            return Err(ParseError::new(s, s.trim(), "three sides"));
+        }
         sides.sort();
         Ok(Triangle { sides })
     }
 }
 
-fn count_correct(s: &str) -> usize {
-    s.lines()
-        .map(|l| Triangle::from_str(l).unwrap())
+fn count_correct(s: &str) -> Result<usize, ParseError> {
+    Ok(parse::parse_lines::<Triangle>(s)?
+        .iter()
         .filter(|t| t.is_correct())
-        .count()
+        .count())
 }
 
-fn count_correct_horizontal(s: &str) -> usize {
+fn count_correct_horizontal(s: &str) -> Result<usize, ParseError> {
     let mut transformed = String::new();
 
     s.lines()
@@ -50,9 +58,25 @@ fn count_correct_horizontal(s: &str) -> usize {
     count_correct(transformed.as_str())
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day3.txt").unwrap();
-    println!("{:?}", count_correct_horizontal(content.as_str()));
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        // Part 2 regroups the rows, so check them here where the line numbers
+        // still match the input.
+        parse::parse_lines::<Triangle>(input)?;
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_correct(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(count_correct_horizontal(input)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -64,7 +88,7 @@ mod test {
         assert_eq!(
             Triangle::from_str("  785  516  744").unwrap(),
             Triangle {
-                sides: vec![785, 516, 744]
+                sides: vec![516, 744, 785]
             }
         );
     }
@@ -75,4 +99,13 @@ mod test {
         assert!(!Triangle::from_str("  85  56  744").unwrap().is_correct());
         assert!(Triangle::from_str("  700  500  201").unwrap().is_correct());
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Triangle::from_str("  785  5l6  744").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (8, "5l6"));
+        let error = Day3::parse("1 2 3\n  4 5").unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line, error.column), (Some(2), 3));
+    }
 }
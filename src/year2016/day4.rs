@@ -1,9 +1,16 @@
-use std::{fs::read_to_string, str::FromStr};
+use std::{cmp::Reverse, str::FromStr};
 
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 
+use crate::{
+    combinator::{self, literal, take_while1, Parser},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
+
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Room {
+pub struct Room {
     name: Vec<String>,
     id: usize,
     checksum: String,
@@ -23,6 +30,10 @@ fn group_by_equality<T: Eq + Clone + Ord>(mut vec: Vec<T>) -> Vec<Vec<T>> {
         }
     }
 
+    if !current_group.is_empty() {
+        result.push(current_group);
+    }
+
     result
 }
 
@@ -38,7 +49,7 @@ impl Room {
 
         let mut grouped = group_by_equality(letters);
 
-        grouped.sort_unstable_by(|a, b| b.len().cmp(&a.len()));
+        grouped.sort_by_key(|group| Reverse(group.len()));
 
         grouped
             .into_iter()
@@ -57,28 +68,29 @@ impl Room {
     }
 }
 
+fn room<'a>() -> impl Parser<'a, Room> {
+    let lowercase = |expected| take_while1(expected, |c| c.is_ascii_lowercase());
+    let sector_id = take_while1("a sector id", |c| c.is_ascii_alphanumeric())
+        .try_map("a number", |id| id.parse().ok());
+    lowercase("a lowercase letter")
+        .separated_by(literal("-"))
+        .then_ignore(literal("-"))
+        .then(sector_id)
+        .then_ignore(literal("["))
+        .then(lowercase("a checksum"))
+        .then_ignore(literal("]"))
+        .map(|((name, id), checksum)| Room {
+            name: name.into_iter().map(str::to_string).collect(),
+            id,
+            checksum: checksum.to_string(),
+        })
+}
+
 impl FromStr for Room {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut s_iter = s.split("[");
-
-        let name_and_id = s_iter.next().unwrap();
-        let checksum: String = s_iter
-            .next()
-            .ok_or("Invalid format")?
-            .trim_end_matches("]")
-            .to_string();
-
-        let ni_iter = name_and_id.rsplitn(2, "-");
-        let id_str = ni_iter.clone().next().ok_or("Invalid id format")?;
-        let id: usize = id_str.parse().map_err(|_| "ID is not a valid number")?;
-        let name: Vec<String> = name_and_id[..name_and_id.len() - id_str.len() - 1]
-            .split('-')
-            .map(|s| s.to_string())
-            .collect();
-
-        Ok(Self { name, id, checksum })
+        combinator::parse_line(room(), s)
     }
 }
 
@@ -89,24 +101,34 @@ fn decrypt(s: &str, n: usize) -> String {
         .collect()
 }
 
-fn get_ids_sum(input: &str) -> usize {
-    input
-        .lines()
-        .map(|l| Room::from_str(l).unwrap())
+fn get_ids_sum(rooms: &[Room]) -> usize {
+    rooms
+        .iter()
         .filter(|r| r.is_correct())
         .fold(0, |acc, r| acc + r.id)
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day4.txt").unwrap();
-    println!(
-        "{:?}",
-        content
-            .lines()
-            .map(|r| Room::from_str(r).unwrap())
-            .map(|r| { r.decrypt_name() })
-            .collect_vec()
-    );
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Room>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::parse_lines(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(get_ids_sum(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        input
+            .iter()
+            .filter(|r| r.is_correct())
+            .find(|r| r.decrypt_name().contains("northpole"))
+            .map(|r| r.id.into())
+            .ok_or_else(|| anyhow!("no room stores the north pole objects"))
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +152,15 @@ mod test {
                 checksum: "qhiwf".to_string()
             }
         );
+
+        let error = Room::from_str("abc-d3f-660[abcdf]").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (6, "3"));
+        let error = Room::from_str("abc-def-6x0[abcdf]").unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (9, "a lowercase letter or a number")
+        );
+        assert_eq!(Room::from_str("abc-660").unwrap_err().column, 8);
     }
 
     #[test]
@@ -141,7 +172,8 @@ mod test {
                 vec!['b', 'b', 'b', 'b', 'b', 'b', 'b', 'b'],
                 vec!['c', 'c'],
                 vec!['d', 'd', 'd'],
-                vec!['s']
+                vec!['s'],
+                vec!['v', 'v']
             ]
         )
     }
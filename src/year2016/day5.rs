@@ -1,4 +1,6 @@
-use md5;
+use anyhow::Result;
+
+use crate::solution::{Answer, Solution};
 
 fn generate_hash(n: u32, prefix: &str) -> md5::Digest {
     md5::compute(format!("{}{}", prefix, n))
@@ -34,19 +36,10 @@ fn decode_clever_password(prefix: &str) -> String {
         let hash = generate_hash(n, prefix);
         if starts_with_five_zeros(hash) {
             let hash_string = format!("{:x}", hash);
-            if let Some(digit) = hash_string.clone().chars().nth(5).unwrap().to_digit(10) {
-                match digit {
-                    0..=7 => {
-                        if password
-                            .into_iter()
-                            .nth(usize::try_from(digit).unwrap())
-                            .is_none()
-                        {
-                            password[digit as usize] = hash_string.clone().chars().nth(6);
-                            elems += 1;
-                        }
-                    }
-                    _ => (),
+            if let Some(digit @ 0..=7) = hash_string.chars().nth(5).unwrap().to_digit(10) {
+                if password[digit as usize].is_none() {
+                    password[digit as usize] = hash_string.chars().nth(6);
+                    elems += 1;
                 }
             }
         }
@@ -57,9 +50,22 @@ fn decode_clever_password(prefix: &str) -> String {
     password.into_iter().map(|c| c.unwrap()).collect()
 }
 
-pub fn solve() {
-    let content = "wtnhxymk";
-    println!("{}", decode_clever_password(content));
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(decode_password(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(decode_clever_password(input).into())
+    }
 }
 
 #[cfg(test)]
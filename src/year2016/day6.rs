@@ -1,26 +1,86 @@
-use std::{collections::HashMap, fs::read_to_string, hash::Hash};
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Solution},
+};
 
 type Alphabet = HashMap<char, usize>;
 
-fn construct_correct(s: &str) -> String {
+/// Splits the messages into characters, checking they are all as long as
+/// the first.
+fn parse_messages(input: &str) -> Result<Vec<Vec<char>>, ParseError> {
+    let width = input.lines().next().map_or(0, |line| line.chars().count());
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| match line.chars().count() {
+            len if len == width => Ok(line.chars().collect()),
+            _ => Err(ParseError::new(line, line, format!("{} characters", width)).at_line(n + 1)),
+        })
+        .collect()
+}
+
+fn construct_correct(messages: &[Vec<char>], most_common: bool) -> String {
     let mut decoded = String::new();
-    for i in 0..8 {
+    for i in 0..messages.first().map_or(0, Vec::len) {
         let mut counts = Alphabet::new();
-        for line in s.lines() {
+        for message in messages {
             counts
-                .entry(line.chars().nth(i).unwrap())
+                .entry(message[i])
                 .and_modify(|e| *e += 1)
                 .or_insert(1);
         }
-        counts
-            .iter()
-            .min_by_key(|&(_, count)| count)
-            .map(|(c, _)| decoded.push(*c));
+        let picked = if most_common {
+            counts.iter().max_by_key(|&(_, count)| count)
+        } else {
+            counts.iter().min_by_key(|&(_, count)| count)
+        };
+        if let Some((c, _)) = picked {
+            decoded.push(*c)
+        }
     }
     decoded
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day6.txt").unwrap();
-    println!("{}", construct_correct(&content));
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Vec<char>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_messages(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(construct_correct(input, true).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(construct_correct(input, false).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    const EXAMPLE: &str = "eedadn\ndrvtee\neandsr\nraavrd\natevrs\ntsrnev\nsdttsa\nrasrtv\n\
+                           nssdts\nntnada\nsvetve\ntesnvt\nvntsnd\nvrdear\ndvrsen\nenarar";
+
+    examples!(Day6 {
+        example: EXAMPLE => part1: "easter", part2: "advent";
+    });
+
+    #[test]
+    fn test_parse_short_line() {
+        let error = parse_messages("eedadn\ndrv\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.text.as_str(), error.expected.as_str()),
+            (Some(2), "drv", "6 characters")
+        );
+    }
 }
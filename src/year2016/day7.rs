@@ -1,14 +1,37 @@
-use std::fs::read_to_string;
+use anyhow::Result;
+
+use crate::{
+    geometry,
+    parse::ParseError,
+    solution::{Answer, Solution},
+};
 
 fn is_abba(s: &[u8]) -> bool {
     s[0] == s[3] && s[1] == s[2] && s[0] != s[1]
 }
 
+/// Checks that every address is made of lowercase letters and brackets.
+fn parse_addresses(input: &str) -> Result<Vec<String>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| {
+            if line.is_empty() {
+                return Err(ParseError::end(line, "an address").at_line(n + 1));
+            }
+            geometry::parse_chars(line, "a lowercase letter or a bracket", |c| {
+                matches!(c, 'a'..='z' | '[' | ']').then_some(c)
+            })
+            .map(|chars| chars.into_iter().collect())
+            .map_err(|e| e.at_line(n + 1))
+        })
+        .collect()
+}
+
 fn is_ip_tls(ip: &str) -> bool {
     let mut in_brackets = false;
     let mut has_abba = false;
-    for i in 0..ip.len() - 3 {
-        let s = &ip.as_bytes()[i..i + 4];
+    for s in ip.as_bytes().windows(4) {
         if s[0] == b'[' {
             in_brackets = true;
         } else if s[0] == b']' {
@@ -48,10 +71,22 @@ fn is_ip_ssl(ip: &str) -> bool {
     false
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day7.txt").unwrap();
-    let result = content.lines().filter(|ip| is_ip_ssl(ip)).count();
-    println!("{}", result);
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_addresses(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(input.iter().filter(|ip| is_ip_tls(ip)).count().into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(input.iter().filter(|ip| is_ip_ssl(ip)).count().into())
+    }
 }
 
 #[cfg(test)]
@@ -60,8 +95,22 @@ mod tests {
 
     #[test]
     fn test_is_abba() {
-        assert_eq!(is_abba(b"abba"), true);
-        assert_eq!(is_abba(b"aaaa"), false);
-        assert_eq!(is_abba(b"abca"), false);
+        assert!(is_abba(b"abba"));
+        assert!(!is_abba(b"aaaa"));
+        assert!(!is_abba(b"abca"));
+    }
+
+    #[test]
+    fn test_short_addresses() {
+        assert!(!is_ip_tls("abb"));
+        assert!(!is_ip_ssl("a"));
+    }
+
+    #[test]
+    fn test_parse_addresses() {
+        let error = parse_addresses("abba[mnop]qrst\nab-c\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), 3));
+        let error = parse_addresses("abba\n\nqrst").unwrap_err();
+        assert_eq!(error.line, Some(2));
     }
 }
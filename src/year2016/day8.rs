@@ -1,88 +1,107 @@
-use anyhow::{anyhow, Result};
-use std::{fs::read_to_string, str::FromStr};
+use anyhow::Result;
+use std::str::FromStr;
 
-type Screen = [[bool; 50]; 6];
+use crate::{
+    combinator::{self, integer, literal, Parser},
+    explore::{self, Explorer, Helper},
+    geometry::Point,
+    grid::{Grid, Rect},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
-#[derive(Debug, PartialEq)]
-enum Instruction {
+const WIDTH: usize = 50;
+const HEIGHT: usize = 6;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Instruction {
     Rect(usize, usize),
     RotateRow(usize, usize),
     RotateColumn(usize, usize),
 }
 
+fn bounded<'a>(limit: usize, expected: &'static str) -> impl Parser<'a, usize> {
+    integer().try_map(expected, move |n| (n < limit).then_some(n))
+}
+
+fn instruction<'a>() -> impl Parser<'a, Instruction> {
+    let rect = literal("rect ")
+        .ignore_then(bounded(WIDTH + 1, "a width of at most 50"))
+        .then_ignore(literal("x"))
+        .then(bounded(HEIGHT + 1, "a height of at most 6"))
+        .map(|(width, height)| Instruction::Rect(width, height));
+    let row = literal("row ")
+        .ignore_then(literal("y="))
+        .ignore_then(bounded(HEIGHT, "a row below 6"))
+        .then_ignore(literal(" by "))
+        .then(integer())
+        .map(|(y, amount)| Instruction::RotateRow(y, amount));
+    let column = literal("column ")
+        .ignore_then(literal("x="))
+        .ignore_then(bounded(WIDTH, "a column below 50"))
+        .then_ignore(literal(" by "))
+        .then(integer())
+        .map(|(x, amount)| Instruction::RotateColumn(x, amount));
+    rect.or(literal("rotate ").ignore_then(row.or(column)))
+}
+
 impl FromStr for Instruction {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        let parts: Vec<&str> = s.split_whitespace().collect();
-        match parts[0] {
-            "rect" => {
-                let dimensions: Vec<usize> =
-                    parts[1].split('x').map(|s| s.parse().unwrap()).collect();
-                Ok(Instruction::Rect(dimensions[0], dimensions[1]))
-            }
-            "rotate" => {
-                let index: usize = parts[2][2..].parse().unwrap();
-                let amount: usize = parts[4].parse().unwrap();
-                match parts[1] {
-                    "row" => Ok(Instruction::RotateRow(index, amount)),
-                    "column" => Ok(Instruction::RotateColumn(index, amount)),
-                    _ => Err(anyhow!("Invalid rotation")),
-                }
-            }
-            _ => Err(anyhow!("Invalid instruction")),
-        }
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        combinator::parse_line(instruction(), s)
     }
 }
 
-fn apply_instructions(s: &str) -> Screen {
-    let mut screen: Screen = [[false; 50]; 6];
-    for line in s.lines() {
-        let instruction: Instruction = line.parse().unwrap();
+fn apply_instructions(instructions: &[Instruction]) -> Grid<bool> {
+    let mut screen = Grid::new(WIDTH, HEIGHT, false);
+    for &instruction in instructions {
         match instruction {
-            Instruction::Rect(x, y) => {
-                for i in 0..y {
-                    for j in 0..x {
-                        screen[i][j] = true;
-                    }
-                }
-            }
-            Instruction::RotateRow(y, amount) => {
-                let mut new_row = [false; 50];
-                for i in 0..50 {
-                    new_row[(i + amount) % 50] = screen[y][i];
-                }
-                screen[y] = new_row;
-            }
-            Instruction::RotateColumn(x, amount) => {
-                let mut new_column = [false; 6];
-                for i in 0..6 {
-                    new_column[(i + amount) % 6] = screen[i][x];
-                }
-                for i in 0..6 {
-                    screen[i][x] = new_column[i];
-                }
-            }
+            Instruction::Rect(x, y) => screen.fill_rect(Rect::new(Point::ORIGIN, x, y), true),
+            Instruction::RotateRow(y, amount) => screen.rotate_row(y, amount),
+            Instruction::RotateColumn(x, amount) => screen.rotate_column(x, amount),
         }
     }
     screen
 }
 
-fn draw_screen(screen: &Screen) {
-    for row in screen.iter() {
-        for &cell in row.iter() {
-            print!("{}", if cell { '#' } else { '.' });
-        }
-        println!();
-    }
+fn draw(screen: &Grid<bool>) -> Answer {
+    Answer::from_pixels(&screen.rows().collect::<Vec<_>>())
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day8.txt").unwrap();
+fn explore_screen(input: &str, _args: &str) -> Result<String> {
+    let instructions: Vec<Instruction> = parse::parse_lines(input)?;
+    Ok(draw(&apply_instructions(&instructions)).to_string())
+}
+
+pub const EXPLORER: Explorer = Explorer {
+    parses: "Instruction",
+    parse_line: explore::describe::<Instruction>,
+    helpers: &[Helper {
+        name: "screen",
+        usage: "",
+        help: "draw the screen after applying the instructions",
+        run: explore_screen,
+    }],
+};
+
+pub struct Day8;
 
-    let screen = apply_instructions(&content);
+impl Solution for Day8 {
+    type Input = Vec<Instruction>;
 
-    draw_screen(&screen);
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::parse_lines(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let screen = apply_instructions(input);
+        Ok(screen.values().filter(|&&lit| lit).count().into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(draw(&apply_instructions(input)))
+    }
 }
 
 #[cfg(test)]
@@ -103,5 +122,29 @@ mod tests {
             "rotate row y=0 by 4".parse::<Instruction>().unwrap(),
             Instruction::RotateRow(0, 4)
         );
+
+        let error = "rotate row x=0 by 4".parse::<Instruction>().unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (12, "x"));
+        let error = "rotate row y=6 by 4".parse::<Instruction>().unwrap_err();
+        assert_eq!(error.expected, "a row below 6");
+        let error = "rect 3x".parse::<Instruction>().unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (8, ""));
+        assert!("rotate row y=0 to 4".parse::<Instruction>().is_err());
+    }
+
+    #[test]
+    fn test_apply_instructions() {
+        let instructions = parse::parse_lines(
+            "rect 3x2\nrotate column x=1 by 1\nrotate row y=0 by 4\nrotate column x=1 by 1",
+        )
+        .unwrap();
+        let screen = apply_instructions(&instructions);
+        assert_eq!(screen.values().filter(|&&lit| lit).count(), 6);
+        let drawn = draw(&screen).to_string();
+        let rows: Vec<&str> = drawn.lines().map(|row| &row[..8]).collect();
+        assert_eq!(
+            rows,
+            vec!["....#.#.", "#.#.....", ".#......", ".#......", "........", "........"]
+        );
     }
 }
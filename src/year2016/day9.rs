@@ -1,56 +1,155 @@
-use std::fs::read_to_string;
-
-fn decompress(s: &str) -> String {
-    let mut result = String::new();
-    let mut chars = s.chars();
-    while let Some(c) = chars.next() {
-        if c == '(' {
-            let mut marker = String::new();
-            while let Some(c) = chars.next() {
-                if c == ')' {
-                    break;
-                }
-                marker.push(c);
-            }
-            let parts: Vec<usize> = marker.split('x').map(|s| s.parse().unwrap()).collect();
-            let mut repeat = String::new();
-            for _ in 0..parts[0] {
-                repeat.push(chars.next().unwrap());
-            }
-            result.push_str(&repeat.repeat(parts[1]));
-        } else {
-            result.push(c);
+use std::ops::Range;
+
+use anyhow::Result;
+
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
+
+/// A run of compressed data: plain text, or a `(AxB)` marker with the range
+/// of the line it repeats.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Chunk {
+    Text(usize),
+    Repeat { times: usize, data: Range<usize> },
+}
+
+/// The compressed line and its top-level chunks. Markers within repeated data
+/// are only parsed when they are expanded.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Compressed {
+    line: String,
+    chunks: Vec<Chunk>,
+}
+
+/// Parses `data`, a slice of `line`, checking that every marker is well formed
+/// and that the data it repeats does not run past the end of `data`. The
+/// repeated data itself is left unparsed.
+fn parse_chunks(line: &str, data: &str) -> Result<Vec<Chunk>, ParseError> {
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let Some(after) = rest.strip_prefix('(') else {
+            let len = rest.find('(').unwrap_or(rest.len());
+            chunks.push(Chunk::Text(len));
+            rest = &rest[len..];
+            continue;
+        };
+        let (marker, after) = after
+            .split_once(')')
+            .ok_or_else(|| ParseError::new(line, rest, "a `(<length>x<times>)` marker"))?;
+        let (len, times) = marker
+            .split_once('x')
+            .ok_or_else(|| ParseError::new(line, marker, "`<length>x<times>`"))?;
+        let len: usize = parse::number(line, len)?;
+        let times = parse::number(line, times)?;
+        if after.len() < len {
+            return Err(ParseError::new(
+                line,
+                after,
+                format!("{} characters to repeat", len),
+            ));
         }
+        let start = after.as_ptr() as usize - line.as_ptr() as usize;
+        chunks.push(Chunk::Repeat {
+            times,
+            data: start..start + len,
+        });
+        rest = &after[len..];
     }
-    result
-}
-
-fn decompress_v2(s: &str) -> usize {
-    let mut result = 0;
-    let mut chars = s.chars();
-    while let Some(c) = chars.next() {
-        if c == '(' {
-            let mut marker = String::new();
-            while let Some(c) = chars.next() {
-                if c == ')' {
-                    break;
-                }
-                marker.push(c);
-            }
-            let parts: Vec<usize> = marker.split('x').map(|s| s.parse().unwrap()).collect();
-            let mut repeat = String::new();
-            for _ in 0..parts[0] {
-                repeat.push(chars.next().unwrap());
+    Ok(chunks)
+}
+
+fn parse_input(input: &str) -> Result<Compressed, ParseError> {
+    let line = input.lines().next().unwrap_or_default();
+    let chunks = parse_chunks(line, line).map_err(|e| e.at_line(1))?;
+    Ok(Compressed {
+        line: line.to_string(),
+        chunks,
+    })
+}
+
+/// The decompressed length when markers within repeated data are left as
+/// they are.
+fn decompressed_len(chunks: &[Chunk]) -> usize {
+    chunks
+        .iter()
+        .map(|chunk| match chunk {
+            Chunk::Text(len) => *len,
+            Chunk::Repeat { times, data } => times * data.len(),
+        })
+        .sum()
+}
+
+/// The decompressed length when markers within repeated data are expanded
+/// too, which fails if any of them is malformed.
+fn decompressed_len_v2(line: &str, chunks: &[Chunk]) -> Result<usize, ParseError> {
+    chunks
+        .iter()
+        .map(|chunk| match chunk {
+            Chunk::Text(len) => Ok(*len),
+            Chunk::Repeat { times, data } => {
+                let nested = parse_chunks(line, &line[data.clone()])?;
+                Ok(times * decompressed_len_v2(line, &nested)?)
             }
-            result += parts[1] * decompress_v2(&repeat);
-        } else {
-            result += 1;
-        }
+        })
+        .sum()
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Compressed;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(decompressed_len(&input.chunks).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let len = decompressed_len_v2(&input.line, &input.chunks).map_err(|e| e.at_line(1))?;
+        Ok(len.into())
     }
-    result
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2016/Day9.txt").unwrap();
-    println!("{}", decompress_v2(&content))
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    examples!(Day9 {
+        plain: "ADVENT" => part1: 6;
+        single: "A(1x5)BC" => part1: 7;
+        whole: "(3x3)XYZ" => part1: 9, part2: 9;
+        two_markers: "A(2x2)BCD(2x2)EFG" => part1: 11;
+        marker_in_data: "(6x1)(1x3)A" => part1: 6;
+        short_nested: "(6x2)(3x2)AB" => part1: 13;
+        unclosed_nested: "(3x2)(AB" => part1: 6;
+        nested: "X(8x2)(3x3)ABCY" => part1: 18, part2: 20;
+        deep: "(27x12)(20x12)(13x14)(7x10)(1x12)A" => part2: 241920;
+        mixed: "(25x3)(3x3)ABC(2x3)XY(5x2)PQRSTX(18x9)(3x2)TWO(5x7)SEVEN" => part2: 445;
+    });
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_input("AB(3y2)C").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (Some(1), 4, "3y2")
+        );
+        let error = parse_input("A(2x)BC").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (5, "a number"));
+        let error = parse_input("A(5x2)BC").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (7, "BC"));
+        let error = parse_input("A(5x2").unwrap_err();
+        assert_eq!(error.column, 2);
+        // A nested marker is only checked when part 2 expands it.
+        let input = parse_input("(6x2)(3x2)AB").unwrap();
+        let error = decompressed_len_v2(&input.line, &input.chunks).unwrap_err();
+        assert_eq!(error.column, 11);
+    }
 }
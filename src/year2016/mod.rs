@@ -1 +1,11 @@
+pub mod day1;
 pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+#[cfg(feature = "hashing")]
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
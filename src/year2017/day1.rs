@@ -1,33 +1,66 @@
-use std::fs::read_to_string;
-
-fn sum_matching(s: String) -> u32 {
-    let (input, _): (Vec<_>, Vec<_>) = s
-        .chars()
-        .zip(s.chars().cycle().skip(1))
-        .filter(|(c1, c2)| c1 == c2)
-        .unzip();
-
-    input
-        .into_iter()
-        .map(|c| c.to_digit(10).unwrap())
-        .fold(0, |acc, x| x + acc)
+use anyhow::Result;
+
+use crate::{
+    geometry::parse_chars,
+    parse::ParseError,
+    solution::{Answer, Solution},
+};
+
+fn parse_digits(input: &str) -> Result<Vec<u32>, ParseError> {
+    let line = input.lines().next().unwrap_or_default();
+    parse_chars(line, "a digit", |c| c.to_digit(10)).map_err(|e| e.at_line(1))
 }
 
-fn sum_matching_halfway(s: String) -> u32 {
-    let (input, _): (Vec<_>, Vec<_>) = s
-        .chars()
-        .zip(s.chars().cycle().skip(s.len() / 2))
-        .filter(|(c1, c2)| c1 == c2)
-        .unzip();
-
-    input
-        .into_iter()
-        .map(|c| c.to_digit(10).unwrap())
-        .fold(0, |acc, x| x + acc)
+/// Sums the digits that match the digit `offset` places further round the
+/// circular list.
+fn sum_matching(digits: &[u32], offset: usize) -> u32 {
+    digits
+        .iter()
+        .zip(digits.iter().cycle().skip(offset))
+        .filter(|(d1, d2)| d1 == d2)
+        .map(|(d, _)| d)
+        .sum()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_digits(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(sum_matching(input, 1).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(sum_matching(input, input.len() / 2).into())
+    }
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2017/Day1.txt").unwrap();
-    // println!("{}", content);
-    println!("{}", sum_matching_halfway(content));
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    examples!(Day1 {
+        pairs: "1122" => part1: 3;
+        all_same: "1111" => part1: 4;
+        no_matches: "1234" => part1: 0;
+        wrap_around: "91212129" => part1: 9;
+        halfway_all: "1212" => part2: 6;
+        halfway_none: "1221" => part2: 0;
+        halfway_one: "123425" => part2: 4;
+        halfway_same: "123123" => part2: 12;
+        halfway_alternating: "12131415" => part2: 4;
+    });
+
+    #[test]
+    fn test_parse_digits() {
+        assert_eq!(parse_digits("1122\n").unwrap(), vec![1, 1, 2, 2]);
+        let error = parse_digits("12a4").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), 3));
+    }
 }
@@ -1,13 +1,23 @@
-use std::fs::read_to_string;
-
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 
-fn get_row_min_max(s: &str) -> (u32, u32) {
-    s.split_whitespace()
-        .map(|s| u32::from_str_radix(&s, 10).unwrap())
-        .minmax()
-        .into_option()
-        .unwrap()
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
+
+type Row = Vec<u32>;
+
+fn parse_rows(input: &str) -> Result<Vec<Row>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| {
+            line.split_whitespace()
+                .map(|cell| parse::number(line, cell).map_err(|e| e.at_line(n + 1)))
+                .collect()
+        })
+        .collect()
 }
 
 fn find_divisible_pair<I>(iter: I) -> Option<(u32, u32)>
@@ -16,9 +26,9 @@ where
     I::IntoIter: Clone,
 {
     iter.into_iter().tuple_combinations().find_map(|(a, b)| {
-        if a % b == 0 {
+        if b != 0 && a % b == 0 {
             Some((a, b))
-        } else if b % a == 0 {
+        } else if a != 0 && b % a == 0 {
             Some((b, a))
         } else {
             None
@@ -26,31 +36,47 @@ where
     })
 }
 
-fn get_row_divisible(s: &str) -> u32 {
-    let nums = s
-        .split_whitespace()
-        .map(|s| u32::from_str_radix(&s, 10).unwrap())
-        .collect::<Vec<u32>>();
-
-    find_divisible_pair(nums).map(|(a, b)| a / b).unwrap()
+fn get_row_divisible(row: &[u32]) -> Option<u32> {
+    find_divisible_pair(row.iter().copied()).map(|(a, b)| a / b)
 }
 
-fn get_hash(s: &str) -> u32 {
-    s.lines()
-        .map(|l| get_row_min_max(l))
-        .map(|(min, max)| max - min)
-        .fold(0, |acc, diff| acc + diff)
+fn get_hash(rows: &[Row]) -> u32 {
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .minmax()
+                .into_option()
+                .map_or(0, |(min, max)| max - min)
+        })
+        .sum()
 }
 
-fn get_hash_div(s: &str) -> u32 {
-    s.lines()
-        .map(|l| get_row_divisible(l))
-        .fold(0, |acc, div| acc + div)
+fn get_hash_div(rows: &[Row]) -> Result<u32> {
+    rows.iter()
+        .enumerate()
+        .map(|(n, row)| {
+            get_row_divisible(row)
+                .ok_or_else(|| anyhow!("row {} has no evenly divisible pair", n + 1))
+        })
+        .sum()
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2017/Day2.txt").unwrap();
-    println!("{}", get_hash_div(&content));
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Row>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_rows(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(get_hash(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(get_hash_div(input)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -59,7 +85,9 @@ mod test {
 
     #[test]
     fn test_get_row_divisible() {
-        assert_eq!(get_row_divisible("5 9 2 8"), 4);
+        assert_eq!(get_row_divisible(&[5, 9, 2, 8]), Some(4));
+        assert_eq!(get_row_divisible(&[0, 7]), Some(0));
+        assert_eq!(get_row_divisible(&[3, 7]), None);
     }
 
     #[test]
@@ -67,4 +95,14 @@ mod test {
         assert_eq!(find_divisible_pair(vec![5, 9, 2, 8]), Some((8, 2)));
         assert_eq!(find_divisible_pair(vec![9, 4, 7, 3]), Some((9, 3)));
     }
+
+    #[test]
+    fn test_parse_rows() {
+        let rows = parse_rows("5 1 9 5\n7 5 3\n2 4 6 8").unwrap();
+        assert_eq!(get_hash(&rows), 18);
+        assert!(get_hash_div(&rows).is_err());
+
+        let error = parse_rows("5 1\n7 x 3").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), 3));
+    }
 }
@@ -1,6 +1,16 @@
-use std::{collections::HashMap, fs::read_to_string, os::unix::raw::gid_t};
+use anyhow::Result;
+
+use crate::{
+    geometry::{Direction, Point, Turn},
+    grid::SparseGrid,
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
 fn find_distance_from_center(n: u32) -> u32 {
+    if n == 1 {
+        return 0;
+    }
     let mut level: u32 = 1;
 
     while (level * 2 - 1).pow(2) < n {
@@ -20,114 +30,70 @@ fn find_distance_from_center(n: u32) -> u32 {
     (level - 1) + (step_to_corner / 2) - dist_from_corner
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct Location(i32, i32);
-
-impl Location {
-    fn update(&self, dir: Direction) -> Self {
-        match dir {
-            Direction::Down => Location(self.0, self.1 - 1),
-            Direction::Up => Location(self.0, self.1 + 1),
-            Direction::Right => Location(self.0 + 1, self.1),
-            Direction::Left => Location(self.0 - 1, self.1),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Right,
-    Left,
-}
-
-impl Direction {
-    fn update(&self) -> Direction {
-        match self {
-            Self::Down => Self::Right,
-            Self::Right => Self::Up,
-            Self::Up => Self::Left,
-            Self::Left => Self::Down,
-        }
-    }
-}
-
 #[derive(Debug, PartialEq)]
 struct Position {
-    location: Location,
+    location: Point<i32>,
     direction: Direction,
 }
 
 impl Position {
+    /// Moves one square along the spiral, which turns anticlockwise at the
+    /// corners and steps out to the next ring from the south-east one.
     fn update(&self) -> Position {
-        let new_dir = match self.location {
-            Location(x, y)
-                if x.abs() == y.abs() && !(x > 0 && y < 0)
-                    || ((x > 0 && y < 0) && x.abs() == y.abs() + 1) =>
-            {
-                self.direction.update()
-            }
-            _ => self.direction,
+        let Point { x, y } = self.location;
+        let south_east = x > 0 && y > 0;
+        let new_dir = if x.abs() == y.abs() && !south_east || south_east && x == y + 1 {
+            self.direction.turn(Turn::Left)
+        } else {
+            self.direction
         };
 
         Self {
-            location: self.location.update(new_dir),
+            location: self.location.step(new_dir),
             direction: new_dir,
         }
     }
-
-    fn get_adjacent_locs(&self) -> Vec<Location> {
-        let mut adjacent_locs = Vec::new();
-        let Location(x, y) = self.location;
-
-        for i in x - 1..=x + 1 {
-            for j in y - 1..=y + 1 {
-                if (i, j) != (x, y) {
-                    adjacent_locs.push(Location(i, j));
-                }
-            }
-        }
-
-        adjacent_locs
-    }
 }
 
 #[derive(Debug)]
 struct Grid {
-    grid: HashMap<Location, u32>,
+    grid: SparseGrid<u32>,
     position: Position,
     biggest: u32,
 }
 
 impl Grid {
     fn new() -> Self {
-        let grid: HashMap<Location, u32> = HashMap::from([
-            (Location(0, 0), 1),
-            (Location(1, 0), 1),
-            (Location(1, 1), 2),
-            (Location(0, 1), 4),
-            (Location(-1, 1), 5),
-            (Location(-1, 0), 10),
-            (Location(-1, -1), 11),
-            (Location(0, -1), 23),
-            (Location(1, -1), 25),
-        ]);
+        let mut grid = SparseGrid::default();
+        for (point, value) in [
+            (Point::new(0, 0), 1),
+            (Point::new(1, 0), 1),
+            (Point::new(1, -1), 2),
+            (Point::new(0, -1), 4),
+            (Point::new(-1, -1), 5),
+            (Point::new(-1, 0), 10),
+            (Point::new(-1, 1), 11),
+            (Point::new(0, 1), 23),
+            (Point::new(1, 1), 25),
+        ] {
+            grid.insert(point, value);
+        }
         Self {
             grid,
             position: Position {
-                location: Location(1, -1),
-                direction: Direction::Right,
+                location: Point::new(1, 1),
+                direction: Direction::East,
             },
             biggest: 25,
         }
     }
 
     fn sum_adjacent(&self) -> u32 {
-        let adjs = self.position.get_adjacent_locs();
-
-        adjs.iter()
-            .fold(0, |acc, loc| acc + self.grid.get(loc).unwrap_or(&0))
+        self.position
+            .location
+            .neighbours8()
+            .map(|loc| self.grid.get(loc))
+            .sum()
     }
 
     fn generate_up_to(n: u32) -> Self {
@@ -143,93 +109,75 @@ impl Grid {
     }
 }
 
-pub fn solve() {
-    let content = "347991";
-    println!("{:#?}", Grid::generate_up_to(347992));
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let line = input.lines().next().unwrap_or_default();
+        let square = parse::number(line, line.trim()).map_err(|e| e.at_line(1))?;
+        if square == 0 {
+            return Err(ParseError::new(line, line.trim(), "a square of at least 1")
+                .at_line(1)
+                .into());
+        }
+        Ok(square)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(find_distance_from_center(*input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(Grid::generate_up_to(*input + 1).biggest.into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::solution::examples;
 
-    #[test]
-    fn test_update_direction() {
-        assert_eq!(Direction::Right.update(), Direction::Up);
-        assert_eq!(Direction::Left.update(), Direction::Down);
-    }
+    examples!(Day3 {
+        center: "1" => part1: 0;
+        first_ring: "12" => part1: 3;
+        second_ring: "23" => part1: 2;
+        far_out: "1024" => part1: 31;
+    });
 
     #[test]
-    fn test_update_location() {
-        assert_eq!(Location(1, 1).update(Direction::Right), Location(2, 1));
-        assert_eq!(Location(1, 10).update(Direction::Down), Location(1, 9));
+    fn test_parse_zero() {
+        let error = Day3::parse("0\n").unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (Some(1), "0"));
     }
 
     #[test]
     fn test_update_position() {
-        assert_eq!(
-            Position {
-                location: Location(1, 1),
-                direction: Direction::Up
-            }
-            .update(),
-            Position {
-                location: Location(0, 1),
-                direction: Direction::Left
+        let update = |location: (i32, i32), direction: Direction| {
+            let position = Position {
+                location: location.into(),
+                direction,
             }
-        );
+            .update();
+            (position.location, position.direction)
+        };
         assert_eq!(
-            Position {
-                location: Location(2, -2),
-                direction: Direction::Right
-            }
-            .update(),
-            Position {
-                location: Location(3, -2),
-                direction: Direction::Right
-            }
+            update((1, -1), Direction::North),
+            (Point::new(0, -1), Direction::West)
         );
         assert_eq!(
-            Position {
-                location: Location(3, -2),
-                direction: Direction::Right
-            }
-            .update(),
-            Position {
-                location: Location(3, -1),
-                direction: Direction::Up
-            }
+            update((2, 2), Direction::East),
+            (Point::new(3, 2), Direction::East)
         );
         assert_eq!(
-            Position {
-                location: Location(3, 3),
-                direction: Direction::Up
-            }
-            .update(),
-            Position {
-                location: Location(2, 3),
-                direction: Direction::Left
-            }
+            update((3, 2), Direction::East),
+            (Point::new(3, 1), Direction::North)
         );
-    }
-
-    #[test]
-    fn test_get_adjacent_locs() {
         assert_eq!(
-            Position {
-                location: Location(5, 5),
-                direction: Direction::Left
-            }
-            .get_adjacent_locs(),
-            vec![
-                Location(4, 4),
-                Location(4, 5),
-                Location(4, 6),
-                Location(5, 4),
-                Location(5, 6),
-                Location(6, 4),
-                Location(6, 5),
-                Location(6, 6)
-            ]
+            update((3, -3), Direction::North),
+            (Point::new(2, -3), Direction::West)
         );
     }
 
@@ -238,4 +186,10 @@ mod test {
         let grid = Grid::new();
         assert_eq!(grid.sum_adjacent(), 25)
     }
+
+    #[test]
+    fn test_generate_up_to() {
+        assert_eq!(Grid::generate_up_to(26).biggest, 26);
+        assert_eq!(Grid::generate_up_to(748).biggest, 806);
+    }
 }
@@ -1,7 +1,8 @@
-use std::fs::read_to_string;
-
+use anyhow::Result;
 use itertools::Itertools;
 
+use crate::solution::{Answer, Solution};
+
 fn is_anagram(s1: &str, s2: &str) -> bool {
     let mut chars1 = s1.chars().collect_vec();
     let mut chars2 = s2.chars().collect_vec();
@@ -34,7 +35,7 @@ fn has_line_anagram(line: &str) -> bool {
 
     for (i, word1) in words.iter().enumerate() {
         for (j, word2) in words.iter().enumerate() {
-            if i != j && is_anagram(&word1, &word2) {
+            if i != j && is_anagram(word1, word2) {
                 return true;
             }
         }
@@ -54,7 +55,21 @@ fn count_non_anagrams(s: &str) -> usize {
 
     correct
 }
-pub fn solve() {
-    let content = read_to_string("inputs/Year2017/Day4.txt").unwrap();
-    println!("{}", count_non_anagrams(&content));
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_non_repeating(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(count_non_anagrams(input).into())
+    }
 }
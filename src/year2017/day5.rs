@@ -1,22 +1,22 @@
-use std::fs::read_to_string;
+use anyhow::Result;
 
-use itertools::Itertools;
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
 type Maze = Vec<i32>;
 
-fn escape_maze(s: &str) -> usize {
+fn escape_maze(maze: &Maze, strange_jumps: bool) -> usize {
     let mut steps = 0;
     let mut index = 0;
 
-    let mut maze: Maze = s
-        .lines()
-        .map(|l| i32::from_str_radix(l, 10).unwrap())
-        .collect_vec();
+    let mut maze = maze.clone();
 
     while let Some(&n) = maze.get(index) {
         let next_index = (index as i32 + n) as usize;
 
-        if n >= 3 {
+        if strange_jumps && n >= 3 {
             maze[index] -= 1;
         } else {
             maze[index] += 1;
@@ -33,8 +33,41 @@ fn escape_maze(s: &str) -> usize {
     steps
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2017/Day5.txt").unwrap();
-    // let content = "0\n3\n0\n1\n-3";
-    println!("{}", escape_maze(&content));
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .enumerate()
+            .map(|(n, line)| parse::number(line, line).map_err(|e| e.at_line(n + 1)))
+            .collect::<Result<_, ParseError>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(escape_maze(input, false).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(escape_maze(input, true).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    examples!(Day5 {
+        maze: "0\n3\n0\n1\n-3" => part1: 5, part2: 10;
+    });
+
+    #[test]
+    fn test_parse_error() {
+        let error = Day5::parse("0\n3\nx\n1").unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (Some(3), "x"));
+    }
 }
@@ -1,35 +1,92 @@
-use std::{collections::HashSet, fs::read_to_string};
+use std::collections::HashSet;
 
-fn find_frequencty(s: &str) -> i32 {
-    s.lines()
-        .map(|l| i32::from_str_radix(l, 10).unwrap())
-        .fold(0, |acc, f| acc + f)
+use anyhow::{anyhow, Result};
+
+use crate::{
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
+
+fn find_frequencty(changes: &[i32]) -> i32 {
+    changes.iter().sum()
 }
 
-fn find_repeat(s: &str) -> i32 {
-    let mut seen = HashSet::new();
-    let mut curr = 0;
+/// The first frequency reached twice, or `None` if none ever is.
+fn find_repeat(changes: &[i32]) -> Option<i32> {
+    let mut sums = vec![0];
+    for change in changes {
+        sums.push(sums.last().unwrap() + change);
+    }
+    let spread = sums.iter().max().unwrap() - sums.iter().min().unwrap();
+    // Every pass shifts the frequencies of the first by the total, so any
+    // repeat comes within as many passes as that shift takes to cross their
+    // spread.
+    let passes = match find_frequencty(changes).unsigned_abs() {
+        0 => 1,
+        total => spread.unsigned_abs() / total + 1,
+    };
 
-    for freq in s
-        .lines()
-        .map(|l| i32::from_str_radix(l, 10).unwrap())
-        .cycle()
-    {
-        // println!("{}, {}, {:?}", curr, freq, seen);
-        if let Some(&f) = seen.get(&(freq + curr)) {
-            return f;
-        } else {
-            curr += freq;
-            seen.insert(curr);
+    let mut seen = HashSet::from([0]);
+    let mut curr = 0;
+    for freq in changes.iter().cycle().take(changes.len() * passes as usize) {
+        curr += freq;
+        if !seen.insert(curr) {
+            return Some(curr);
         }
     }
 
-    curr
+    None
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2018/Day1.txt").unwrap();
-    // let content = "+7\n+7\n-2\n-7\n-4";
-    // let content = "+1\n-1";
-    println!("{}", find_repeat(&content));
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .enumerate()
+            .map(|(n, line)| parse::number(line, line).map_err(|e| e.at_line(n + 1)))
+            .collect::<Result<_, ParseError>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(find_frequencty(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let repeat =
+            find_repeat(input).ok_or_else(|| anyhow!("no frequency is ever reached twice"))?;
+        Ok(repeat.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    examples!(Day1 {
+        back_to_zero: "+1\n-1" => part1: 0, part2: 0;
+        all_increasing: "+1\n+1\n+1" => part1: 3;
+        all_decreasing: "-1\n-2\n-3" => part1: -6;
+        repeat_after_loop: "+3\n+3\n+4\n-2\n-4" => part2: 10;
+        repeat_after_several_loops: "+7\n+7\n-2\n-7\n-4" => part2: 14;
+        repeat_negative_start: "-6\n+3\n+8\n+5\n-6" => part2: 5;
+    });
+
+    #[test]
+    fn test_no_repeat() {
+        assert_eq!(find_repeat(&[1]), None);
+        assert_eq!(find_repeat(&[3, -1]), None);
+        assert_eq!(find_repeat(&[]), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = Day1::parse("+1\n+x\n").unwrap_err();
+        let error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (Some(2), "+x"));
+    }
 }
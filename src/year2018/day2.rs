@@ -1,7 +1,8 @@
-use std::{collections::VecDeque, fs::read_to_string};
-
+use anyhow::{anyhow, Result};
 use itertools::Itertools;
 
+use crate::solution::{Answer, Solution};
+
 fn count_two_three(s: &str) -> u32 {
     fn folding(acc: (u32, u32), el: Vec<String>) -> (u32, u32) {
         let twos = el.clone().into_iter().any(|s| s.len() == 2);
@@ -24,7 +25,7 @@ fn count_two_three(s: &str) -> u32 {
                 .map(|(_, group)| group.collect::<String>())
                 .collect_vec()
         })
-        .fold((0, 0), |acc, el| folding(acc, el));
+        .fold((0, 0), folding);
 
     twos * threes
 }
@@ -38,25 +39,45 @@ fn differs_by_char(s1: &str, s2: &str) -> bool {
         == 1
 }
 
-fn find_similar(s: &str) -> String {
+fn find_similar(s: &str) -> Option<String> {
     let ids: Vec<&str> = s.lines().collect();
 
     for i in 0..ids.len() {
         for j in i + 1..ids.len() {
-            // println!("{}\n{}", ids[i], ids[j]);
             if differs_by_char(ids[i], ids[j]) {
-                return format!("{}\n{}", ids[i], ids[j]);
+                return Some(
+                    ids[i]
+                        .chars()
+                        .zip(ids[j].chars())
+                        .filter(|(c1, c2)| c1 == c2)
+                        .map(|(c, _)| c)
+                        .collect(),
+                );
             }
         }
     }
 
-    "failed".to_string()
+    None
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2018/Day2.txt").unwrap();
-    // let content = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
-    println!("{}", find_similar(&content));
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(count_two_three(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        find_similar(input)
+            .map(Answer::from)
+            .ok_or_else(|| anyhow!("no two box ids differ by exactly one character"))
+    }
 }
 
 #[cfg(test)]
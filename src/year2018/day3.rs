@@ -1,47 +1,40 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs::read_to_string,
-    str::FromStr,
-};
-
-use itertools::Itertools;
+use std::str::FromStr;
 
-type Canvas = HashMap<(u16, u16), Vec<u16>>;
+use anyhow::{anyhow, Result};
 
-fn put_claims_on_canvas(s: &str) -> Canvas {
-    let mut canvas = Canvas::new();
+use crate::{
+    combinator::{self, integer, literal, Parser},
+    geometry::Point,
+    grid::{Grid, Rect},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
-    for claim in s.lines().map(|l| Claim::from_str(l).unwrap()) {
-        for cell in claim.covered_cells() {
-            canvas
-                .entry(cell)
-                .and_modify(|ids| ids.push(claim.id))
-                .or_insert(vec![claim.id]);
-        }
+/// Counts how many claims cover each square inch of the fabric.
+fn put_claims_on_canvas(claims: &[Claim]) -> Grid<u16> {
+    let width = claims
+        .iter()
+        .map(|c| c.rect().origin.x + c.rect().width)
+        .max();
+    let height = claims
+        .iter()
+        .map(|c| c.rect().origin.y + c.rect().height)
+        .max();
+    let mut canvas = Grid::new(width.unwrap_or(0), height.unwrap_or(0), 0);
+
+    for claim in claims {
+        canvas.map_rect(claim.rect(), |&n| n + 1);
     }
 
     canvas
 }
 
-fn num_of_covered_by_more_then_one(canvas: Canvas) -> usize {
-    canvas.values().filter(|v| v.len() > 1).count()
-}
-
-fn get_ids_of_overlapping(canvas: Canvas) -> Vec<u16> {
-    let mut ids = canvas
-        .values()
-        .filter(|v| v.len() > 1)
-        .flat_map(|v| v.clone())
-        .collect_vec();
-
-    ids.sort_unstable();
-    ids.dedup();
-
-    ids
+fn num_of_covered_by_more_then_one(canvas: &Grid<u16>) -> usize {
+    canvas.values().filter(|&&n| n > 1).count()
 }
 
 #[derive(Debug, PartialEq)]
-struct Claim {
+pub struct Claim {
     width: u16,
     height: u16,
     top_offset: u16,
@@ -50,60 +43,93 @@ struct Claim {
 }
 
 impl Claim {
-    fn covered_cells(&self) -> Vec<(u16, u16)> {
-        let mut cells = Vec::new();
-
-        for i in self.left_offset..self.left_offset + self.width {
-            for j in self.top_offset..self.top_offset + self.height {
-                cells.push((i, j))
-            }
-        }
-
-        cells
+    fn rect(&self) -> Rect {
+        Rect::new(
+            Point::new(self.left_offset.into(), self.top_offset.into()),
+            self.width.into(),
+            self.height.into(),
+        )
     }
 }
 
+fn claim<'a>() -> impl Parser<'a, Claim> {
+    literal("#")
+        .ignore_then(integer())
+        .then_ignore(literal(" @ "))
+        .then(integer())
+        .then_ignore(literal(","))
+        .then(integer())
+        .then_ignore(literal(": "))
+        .then(integer())
+        .then_ignore(literal("x"))
+        .then(integer())
+        .map(|((((id, left_offset), top_offset), width), height)| Claim {
+            width,
+            height,
+            top_offset,
+            left_offset,
+            id,
+        })
+}
+
 impl FromStr for Claim {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s
-            .split(&['#', '@', ':'])
-            .skip(1)
-            .map(|s| s.trim())
-            .collect();
-
-        let id: u16 = parts[0].parse().unwrap();
-
-        let offsets: Vec<_> = parts[1]
-            .split(',')
-            .map(|s| s.parse::<u16>().unwrap())
-            .collect();
-
-        let size: Vec<_> = parts[2]
-            .split('x')
-            .map(|s| s.parse::<u16>().unwrap())
-            .collect();
-
-        Ok(Claim {
-            width: size[0],
-            height: size[1],
-            top_offset: offsets[1],
-            left_offset: offsets[0],
-            id,
-        })
+        combinator::parse_line(claim(), s)
+    }
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Claim>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::parse_lines(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(num_of_covered_by_more_then_one(&put_claims_on_canvas(input)).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let canvas = put_claims_on_canvas(input);
+
+        input
+            .iter()
+            .find(|claim| canvas.rect(claim.rect()).all(|&n| n == 1))
+            .map(|claim| Answer::from(claim.id))
+            .ok_or_else(|| anyhow!("every claim overlaps another one"))
     }
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2018/Day3.txt").unwrap();
-    let all_ids: HashSet<u16> = content
-        .lines()
-        .map(|l| Claim::from_str(l).unwrap())
-        .map(|claim| claim.id)
-        .collect();
-    let ids_overlapping =
-        HashSet::from_iter(get_ids_of_overlapping(put_claims_on_canvas(&content)));
-
-    println!("{:?}", all_ids.difference(&ids_overlapping));
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::examples;
+
+    examples!(Day3 {
+        fabric: "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2" => part1: 4, part2: 3;
+    });
+
+    #[test]
+    fn test_parse_claim() {
+        assert_eq!(
+            Claim::from_str("#123 @ 3,2: 5x4").unwrap(),
+            Claim {
+                width: 5,
+                height: 4,
+                top_offset: 2,
+                left_offset: 3,
+                id: 123
+            }
+        );
+
+        let error = Claim::from_str("#123 @ 3,two: 5x4").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (10, "two"));
+        let error = Claim::from_str("#123 @ 3,2").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (11, ""));
+        assert!(Claim::from_str("123 @ 3,2: 5x4").is_err());
+    }
 }
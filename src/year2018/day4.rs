@@ -1,6 +1,12 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, Result};
 use regex::Regex;
-use std::{fs::read_to_string, str::FromStr};
+use std::str::FromStr;
+
+use crate::{
+    explore::{self, Explorer, Helper},
+    parse::{self, ParseError},
+    solution::{Answer, Solution},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 struct DateTime {
@@ -48,7 +54,7 @@ impl Shift {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Guard {
+pub struct Guard {
     id: u16,
     sleep_summary: Vec<[u16; 60]>,
 }
@@ -60,9 +66,9 @@ enum EventKind {
 }
 
 impl FromStr for Event {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let reg: Regex = Regex::new(
             r"(?x)
                 \[
@@ -73,33 +79,44 @@ impl FromStr for Event {
                 \s+
                 (?:Guard\ \#(?P<guard_id>\d{1,4})\ begins\ shift|(?P<message>.+))",
         )
-        .unwrap();
+        .expect("event pattern is valid");
 
-        let caps = match reg.captures(s) {
-            None => return Err(anyhow!("unrecognized event")),
-            Some(caps) => caps,
-        };
+        let caps = reg
+            .captures(s)
+            .ok_or_else(|| ParseError::new(s, s, "`[<yyyy>-<mm>-<dd> <hh>:<mm>] <event>`"))?;
+        let field = |name| parse::number(s, caps.name(name).unwrap().as_str());
+        let minute_text = caps.name("minute").unwrap().as_str();
+        let minute = parse::number(s, minute_text)?;
+        if minute >= 60 {
+            return Err(ParseError::new(s, minute_text, "a minute below 60"));
+        }
 
         let datetime = DateTime {
-            month: caps["month"].parse()?,
-            day: caps["day"].parse()?,
-            hour: caps["hour"].parse()?,
-            minute: caps["minute"].parse()?,
+            month: field("month")?,
+            day: field("day")?,
+            hour: field("hour")?,
+            minute,
         };
 
         let kind = if let Some(guard_id) = caps.name("guard_id") {
-            EventKind::Start(guard_id.as_str().parse()?)
-        } else if &caps["message"] == "falls asleep" {
-            EventKind::FellAsleep
-        } else if &caps["message"] == "wakes up" {
-            EventKind::WakeUp
+            EventKind::Start(parse::number(s, guard_id.as_str())?)
         } else {
-            return Err(anyhow!("Wrong event kind"));
+            match caps.name("message").unwrap().as_str() {
+                "falls asleep" => EventKind::FellAsleep,
+                "wakes up" => EventKind::WakeUp,
+                other => {
+                    return Err(ParseError::new(
+                        s,
+                        other,
+                        "`falls asleep`, `wakes up` or `Guard #<id> begins shift`",
+                    ))
+                }
+            }
         };
 
         Ok(Event {
             time: datetime,
-            kind: kind,
+            kind,
         })
     }
 }
@@ -122,11 +139,8 @@ fn group_shifts_by_guard(shifts: Vec<Shift>) -> Vec<Guard> {
     guards
 }
 
-fn get_sorted_events(content: &str) -> Result<Vec<Event>> {
-    let mut events: Vec<Event> = content
-        .lines()
-        .map(|line| Event::from_str(line))
-        .collect::<Result<Vec<Event>>>()?;
+fn get_sorted_events(content: &str) -> Result<Vec<Event>, ParseError> {
+    let mut events: Vec<Event> = parse::parse_lines(content)?;
     events.sort_by(|a, b| {
         a.time
             .month
@@ -167,12 +181,7 @@ fn find_guard_with_most_sleep(guards: Vec<Guard>) -> u16 {
     let mut max_sleep: u16 = 0;
     let mut guard_id = 0;
     for guard in guards {
-        let sleep = guard
-            .sleep_summary
-            .iter()
-            .flatten()
-            .map(|v| u16::from(*v))
-            .sum();
+        let sleep = guard.sleep_summary.iter().flatten().sum();
         if sleep > max_sleep {
             max_sleep = sleep;
             guard_id = guard.id;
@@ -188,33 +197,95 @@ fn get_minute_most_asleep(guard: &Guard) -> (usize, u16) {
         .unwrap()
 }
 
-pub fn solve() {
-    let content = read_to_string("inputs/Year2018/Day4.txt").unwrap();
-    let events = get_sorted_events(&content).unwrap();
-    let shifts = group_events_by_shifts(events);
-    let guards = group_shifts_by_guard(shifts);
-    let most_asleep_guard = find_guard_with_most_sleep(guards.clone());
-    let minute_most_asleep =
-        get_minute_most_asleep(guards.iter().find(|g| g.id == most_asleep_guard).unwrap());
-    let minute_total_most_asleep = guards
-        .into_iter()
-        .map(|g| {
-            let minute = get_minute_most_asleep(&g);
-            (g.id, minute)
+fn explore_guards(input: &str, _args: &str) -> Result<String> {
+    let mut guards = Day4::parse(input)?;
+    guards.sort_by_key(|g| g.id);
+    let lines: Vec<String> = guards
+        .iter()
+        .map(|guard| {
+            let asleep: u16 = guard.sleep_summary.iter().flatten().sum();
+            let (minute, times) = get_minute_most_asleep(guard);
+            format!(
+                "guard #{}: {} shifts, {} minutes asleep, most often at 00:{:02} ({} times)",
+                guard.id,
+                guard.sleep_summary.len(),
+                asleep,
+                minute,
+                times
+            )
         })
-        .max_by(|a, b| a.1 .1.cmp(&b.1 .1))
-        .unwrap();
-    println!(
-        "{:#?}",
-        minute_total_most_asleep.0 as usize * minute_total_most_asleep.1 .0
-    );
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+pub const EXPLORER: Explorer = Explorer {
+    parses: "Event",
+    parse_line: explore::describe::<Event>,
+    helpers: &[Helper {
+        name: "guards",
+        usage: "",
+        help: "summarize the sleep of each guard",
+        run: explore_guards,
+    }],
+};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Guard>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let events = get_sorted_events(input)?;
+        let shifts = group_events_by_shifts(events);
+        Ok(group_shifts_by_guard(shifts))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        let most_asleep_guard = find_guard_with_most_sleep(input.clone());
+        let guard = input
+            .iter()
+            .find(|g| g.id == most_asleep_guard)
+            .ok_or_else(|| anyhow!("no guard ever fell asleep"))?;
+        let (minute, _) = get_minute_most_asleep(guard);
+        Ok((most_asleep_guard as usize * minute).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        let (guard_id, (minute, _)) = input
+            .iter()
+            .map(|g| (g.id, get_minute_most_asleep(g)))
+            .max_by(|a, b| a.1 .1.cmp(&b.1 .1))
+            .ok_or_else(|| anyhow!("no guards on duty"))?;
+        Ok((guard_id as usize * minute).into())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::solution::examples;
 
-    static content: &str = r#"[1518-11-22 23:47] Guard #2969 begins shift
+    examples!(Day4 {
+        guards: "[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up" => part1: 240, part2: 4455;
+    });
+
+    const EVENTS: &str = r#"[1518-11-22 23:47] Guard #2969 begins shift
                          [1518-05-03 00:27] wakes up
                          [1518-05-03 00:02] Guard #349 begins shift
                          [1518-05-03 00:24] falls asleep
@@ -249,12 +320,18 @@ mod test {
                 kind: EventKind::Start(2969)
             }
         );
+
+        let error = Event::from_str("[1518-05-03 00:27] dozes off").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (20, "dozes off"));
+        assert!(Event::from_str("1518-05-03 00:27 wakes up").is_err());
+        let error = Event::from_str("[1518-05-03 00:75] wakes up").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (16, "75"));
     }
 
     #[test]
     fn test_get_sorted_events() {
         assert_eq!(
-            get_sorted_events(content).unwrap(),
+            get_sorted_events(EVENTS).unwrap(),
             vec![
                 Event {
                     time: DateTime {
@@ -334,7 +411,7 @@ mod test {
 
     #[test]
     fn test_group_events_by_shift() {
-        let events = get_sorted_events(content).unwrap();
+        let events = get_sorted_events(EVENTS).unwrap();
         assert_eq!(
             group_events_by_shifts(events),
             vec![
@@ -408,7 +485,7 @@ mod test {
 
     #[test]
     fn test_sleep_summary() {
-        let events = get_sorted_events(content).unwrap();
+        let events = get_sorted_events(EVENTS).unwrap();
         let shifts = group_events_by_shifts(events);
         assert_eq!(
             shifts[0].sleep_summary(),
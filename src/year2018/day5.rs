@@ -1,4 +1,9 @@
-use std::fs::read_to_string;
+use anyhow::Result;
+
+use crate::{
+    solution::{Answer, Solution},
+    trace,
+};
 
 fn react(input: &str) -> usize {
     let mut stack: Vec<char> = Vec::new();
@@ -10,7 +15,7 @@ fn react(input: &str) -> usize {
             }
         }
         stack.push(c);
-        println!("{:?}", stack);
+        trace!("{:?}", stack);
     }
     stack.len()
 }
@@ -32,15 +37,27 @@ fn react2(input: &str, ignore: char) -> usize {
     stack.len()
 }
 
-fn check_ignores(input: &str) {
-    for c in b'a'..=b'z' {
-        let c = c as char;
-        let len = react2(input, c);
-        println!("{}: {}", c, len);
-    }
+fn check_ignores(input: &str) -> usize {
+    (b'a'..=b'z')
+        .map(|c| react2(input, c as char))
+        .min()
+        .unwrap_or(0)
 }
 
-pub fn solve() {
-    let content = "dabAcCaCBAcCcaDA";
-    check_ignores(&content);
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(react(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(check_ignores(input).into())
+    }
 }